@@ -0,0 +1,597 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Arc;
+
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::columnar::{Column, StrColumn};
+use regex::Regex;
+use tantivy::store::StoreReader;
+use tantivy::{DocAddress, DocId, Score, SegmentOrdinal, SegmentReader, TantivyDocument};
+
+use crate::types::SearchSort;
+use crate::unicode_norm::{ancestor_dir_keys, nfc};
+
+/// 按存储的文档内容筛选命中的文档
+pub type DocFilter = Arc<dyn Fn(&TantivyDocument) -> bool + Send + Sync>;
+
+/// 同一文件的命中：父文档（或未拆分的完整文档）以及得分最高的段落子文档
+#[derive(Clone, Default)]
+pub struct FileHit {
+    pub doc: Option<(Score, DocAddress)>,
+    pub passage: Option<(Score, DocAddress)>,
+    /// 命中的段落数
+    pub passage_hits: u32,
+    /// 文件修改时间和大小，用于排序
+    pub modified_time: Option<i64>,
+    pub file_size: Option<u64>,
+    /// 按文件名排序时的排序键（NFC、小写的文件名）
+    pub name: Option<String>,
+}
+
+impl FileHit {
+    /// 文件得分：父文档得分加上最佳段落得分
+    pub fn score(&self) -> Score {
+        self.doc.map(|(s, _)| s).unwrap_or(0.0) + self.passage.map(|(s, _)| s).unwrap_or(0.0)
+    }
+
+    fn merge(&mut self, other: FileHit) {
+        self.doc = better(self.doc, other.doc);
+        self.passage = better(self.passage, other.passage);
+        self.passage_hits += other.passage_hits;
+        self.modified_time = self.modified_time.or(other.modified_time);
+        self.file_size = self.file_size.or(other.file_size);
+        self.name = self.name.take().or(other.name);
+    }
+}
+
+fn better(
+    a: Option<(Score, DocAddress)>,
+    b: Option<(Score, DocAddress)>,
+) -> Option<(Score, DocAddress)> {
+    match (a, b) {
+        (Some(x), Some(y)) => Some(if y.0 > x.0 { y } else { x }),
+        (x, None) => x,
+        (None, y) => y,
+    }
+}
+
+/// 筛选面板中参与分面统计的条件：文件类型、目录和修改时间
+///
+/// 统计分面时这些条件不作为查询子句，而在收集器中按快速字段逐个文件判断，
+/// 同一次搜索即可得到每个分面不应用它自身条件时的命中数
+#[derive(Clone, Default)]
+pub struct FacetFilters {
+    /// 文件类型（分组已展开为扩展名）
+    pub file_types: Option<HashSet<String>>,
+    /// 目录的匹配规则，文件所在的任一级目录键匹配其中之一即可
+    pub dirs: Option<Vec<Regex>>,
+    /// 修改时间区间（毫秒，包含两端）
+    pub modified: Option<(i64, i64)>,
+}
+
+/// 命中文件的分面取值，以及是否满足各项分面筛选条件
+#[derive(Clone)]
+pub struct FileFacets {
+    pub file_type: Option<String>,
+    pub root_dir: Option<String>,
+    pub modified_time: Option<i64>,
+    pub type_ok: bool,
+    pub dir_ok: bool,
+    pub date_ok: bool,
+}
+
+impl FileFacets {
+    /// 不统计分面时，命中的文件都满足条件
+    fn unfiltered() -> Self {
+        Self {
+            file_type: None,
+            root_dir: None,
+            modified_time: None,
+            type_ok: true,
+            dir_ok: true,
+            date_ok: true,
+        }
+    }
+
+    /// 是否满足全部分面筛选条件，即是否计入搜索结果
+    pub fn is_match(&self) -> bool {
+        self.type_ok && self.dir_ok && self.date_ok
+    }
+}
+
+/// 收集结果：排名最前的文件级命中，以及全部命中文件（以路径的哈希为键）的分面取值，
+/// 用于统计命中文件总数和分面计数，不受数量上限影响
+#[derive(Default)]
+pub struct FileGroups {
+    pub hits: HashMap<String, FileHit>,
+    pub matched: HashMap<u64, FileFacets>,
+}
+
+impl FileGroups {
+    /// 满足全部条件的文件总数
+    pub fn total(&self) -> usize {
+        self.matched.values().filter(|f| f.is_match()).count()
+    }
+}
+
+fn path_hash(path: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 读取文档在字符串快速字段中的第一个值
+fn first_str(column: &StrColumn, doc: DocId) -> Option<String> {
+    let ord = column.ords().first(doc)?;
+    let mut out = String::new();
+    column.ord_to_str(ord, &mut out).ok()?.then_some(out)
+}
+
+/// 按排序方式比较两个文件级命中，排在前面的较小；排序字段相同时按相关度，缺少排序字段的文件排在最后
+pub fn rank_cmp(a: &FileHit, b: &FileHit, sort: SearchSort) -> Ordering {
+    fn missing_last<T: Ord>(a: Option<T>, b: Option<T>, desc: bool) -> Ordering {
+        match (a, b) {
+            (Some(x), Some(y)) if desc => y.cmp(&x),
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+    let by_key = match sort {
+        SearchSort::Relevance => Ordering::Equal,
+        SearchSort::Newest => missing_last(a.modified_time, b.modified_time, true),
+        SearchSort::Oldest => missing_last(a.modified_time, b.modified_time, false),
+        SearchSort::Largest => missing_last(a.file_size, b.file_size, true),
+        SearchSort::Smallest => missing_last(a.file_size, b.file_size, false),
+        SearchSort::Name => missing_last(a.name.as_ref(), b.name.as_ref(), false),
+    };
+    by_key.then_with(|| b.score().total_cmp(&a.score()))
+}
+
+/// 文件名排序使用的排序键
+fn name_key(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_name()
+        .map(|n| nfc(&n.to_string_lossy()).to_lowercase())
+}
+
+/// 只保留按排序方式排在最前的 `max` 个文件
+fn retain_top<K: Eq + Hash + Clone>(hits: &mut HashMap<K, FileHit>, max: usize, sort: SearchSort) {
+    if hits.len() <= max {
+        return;
+    }
+    let mut ranked: Vec<(&K, &FileHit)> = hits.iter().collect();
+    ranked.select_nth_unstable_by(max, |a, b| rank_cmp(a.1, b.1, sort));
+    let evicted: Vec<K> = ranked[max..].iter().map(|(k, _)| (*k).clone()).collect();
+    for k in evicted {
+        hits.remove(&k);
+    }
+}
+
+/// 按文件路径归组的收集器：一次搜索中将父文档与段落子文档合并为文件级命中
+///
+/// 最多保留按排序方式排在最前的 `max_files` 个文件（按修改时间、大小或文件名排序时以快速字段的值排名，相同时按得分）；
+/// 同一文件的父文档和段落可能分布在不同的段中，段内不截断，合并各段的命中后再按完整的文件得分取前 `max_files` 个
+///
+/// 同时记录全部命中的文件（不受上限影响），同一文件的文档可能分布在多个段中，按路径去重后得到命中文件总数；
+/// 统计分面时按快速字段判断每个文件是否满足分面筛选条件，只有全部满足的文件进入排名
+///
+/// 设置了文档筛选时，逐个读取命中文档的存储内容校验，不满足的文档（父文档或段落）不计入
+pub struct FileGroupCollector {
+    max_files: usize,
+    sort: SearchSort,
+    /// 统计分面时的分面筛选条件
+    facets: Option<Arc<FacetFilters>>,
+    filter: Option<DocFilter>,
+}
+
+impl FileGroupCollector {
+    pub fn with_limit(max_files: usize) -> Self {
+        Self {
+            max_files: max_files.max(1),
+            sort: SearchSort::Relevance,
+            facets: None,
+            filter: None,
+        }
+    }
+
+    pub fn with_sort(mut self, sort: SearchSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// 统计分面：读取命中文件的文件类型、索引目录和修改时间，并在收集时应用分面筛选条件
+    pub fn with_facets(mut self, facets: Option<FacetFilters>) -> Self {
+        self.facets = facets.map(Arc::new);
+        self
+    }
+
+    pub fn with_filter(mut self, filter: Option<DocFilter>) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+impl Collector for FileGroupCollector {
+    type Fruit = FileGroups;
+    type Child = FileGroupSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        let paths = reader.fast_fields().str("file_path")?.ok_or_else(|| {
+            tantivy::TantivyError::SchemaError("file_path is not a fast field".to_string())
+        })?;
+        // 段内没有段落子文档时该列不存在
+        let passage_no = reader.fast_fields().column_opt::<u64>("passage_no")?;
+        let modified_time = reader.fast_fields().column_opt::<i64>("modified_time")?;
+        let file_size = reader.fast_fields().column_opt::<u64>("file_size")?;
+        // 只在需要分面统计时读取；旧版索引中这两个字段不是快速字段，不统计
+        let (file_types, root_dirs) = if self.facets.is_some() {
+            (
+                reader.fast_fields().str("file_type").ok().flatten(),
+                reader.fast_fields().str("root_dir").ok().flatten(),
+            )
+        } else {
+            (None, None)
+        };
+        let store = match &self.filter {
+            Some(filter) => Some((filter.clone(), reader.get_store_reader(50)?)),
+            None => None,
+        };
+        Ok(FileGroupSegmentCollector {
+            segment_ord: segment_local_id,
+            paths,
+            passage_no,
+            modified_time,
+            file_size,
+            file_types,
+            root_dirs,
+            sort: self.sort,
+            facets: self.facets.clone(),
+            store,
+            hits: HashMap::new(),
+            matched: HashMap::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, fruits: Vec<Self::Fruit>) -> tantivy::Result<Self::Fruit> {
+        let mut merged = FileGroups::default();
+        for fruit in fruits {
+            for (path, hit) in fruit.hits {
+                merged.hits.entry(path).or_default().merge(hit);
+            }
+            for (key, facets) in fruit.matched {
+                merged.matched.entry(key).or_insert(facets);
+            }
+        }
+        retain_top(&mut merged.hits, self.max_files, self.sort);
+        Ok(merged)
+    }
+}
+
+pub struct FileGroupSegmentCollector {
+    segment_ord: SegmentOrdinal,
+    paths: StrColumn,
+    passage_no: Option<Column<u64>>,
+    modified_time: Option<Column<i64>>,
+    file_size: Option<Column<u64>>,
+    file_types: Option<StrColumn>,
+    root_dirs: Option<StrColumn>,
+    sort: SearchSort,
+    facets: Option<Arc<FacetFilters>>,
+    store: Option<(DocFilter, StoreReader)>,
+    /// 以段内文件路径序号为键的命中
+    hits: HashMap<u64, FileHit>,
+    /// 段内全部命中文件的分面取值，以路径序号为键
+    matched: HashMap<u64, FileFacets>,
+}
+
+impl FileGroupSegmentCollector {
+    /// 读取文件的分面取值并判断分面筛选条件；这些都是文件级的值，父文档与段落子文档相同
+    fn file_facets(&self, doc: DocId, ord: u64) -> FileFacets {
+        let Some(filters) = &self.facets else {
+            return FileFacets::unfiltered();
+        };
+        let file_type = self.file_types.as_ref().and_then(|c| first_str(c, doc));
+        let modified_time = self.modified_time.as_ref().and_then(|c| c.first(doc));
+        let type_ok = filters
+            .file_types
+            .as_ref()
+            .is_none_or(|types| file_type.as_ref().is_some_and(|t| types.contains(t)));
+        let dir_ok = filters.dirs.as_ref().is_none_or(|patterns| {
+            let mut path = String::new();
+            self.paths.ord_to_str(ord, &mut path).unwrap_or(false)
+                && ancestor_dir_keys(Path::new(&path))
+                    .iter()
+                    .any(|d| patterns.iter().any(|p| p.is_match(d)))
+        });
+        let date_ok = filters
+            .modified
+            .is_none_or(|(start, end)| modified_time.is_some_and(|t| start <= t && t <= end));
+        FileFacets {
+            file_type,
+            root_dir: self.root_dirs.as_ref().and_then(|c| first_str(c, doc)),
+            modified_time,
+            type_ok,
+            dir_ok,
+            date_ok,
+        }
+    }
+}
+
+impl SegmentCollector for FileGroupSegmentCollector {
+    type Fruit = FileGroups;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        let Some(ord) = self.paths.ords().first(doc) else {
+            return;
+        };
+        if let Some((filter, store)) = &self.store {
+            if !store.get::<TantivyDocument>(doc).is_ok_and(|d| filter(&d)) {
+                return;
+            }
+        }
+        if !self.matched.contains_key(&ord) {
+            let facets = self.file_facets(doc, ord);
+            self.matched.insert(ord, facets);
+        }
+        if !self.matched[&ord].is_match() {
+            return;
+        }
+        let addr = DocAddress::new(self.segment_ord, doc);
+        let is_passage = self
+            .passage_no
+            .as_ref()
+            .is_some_and(|c| c.first(doc).is_some());
+        let hit = self.hits.entry(ord).or_default();
+        if hit.modified_time.is_none() {
+            hit.modified_time = self.modified_time.as_ref().and_then(|c| c.first(doc));
+        }
+        if hit.file_size.is_none() {
+            hit.file_size = self.file_size.as_ref().and_then(|c| c.first(doc));
+        }
+        if hit.name.is_none() && self.sort == SearchSort::Name {
+            let mut path = String::new();
+            if self.paths.ord_to_str(ord, &mut path).unwrap_or(false) {
+                hit.name = name_key(&path);
+            }
+        }
+        if is_passage {
+            hit.passage = better(hit.passage, Some((score, addr)));
+            hit.passage_hits += 1;
+        } else {
+            hit.doc = better(hit.doc, Some((score, addr)));
+        }
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        let mut out = FileGroups::default();
+        let mut path = String::new();
+        for (ord, facets) in self.matched {
+            path.clear();
+            if self.paths.ord_to_str(ord, &mut path).unwrap_or(false) {
+                out.matched.insert(path_hash(&path), facets);
+            }
+        }
+        for (ord, hit) in self.hits {
+            path.clear();
+            if self.paths.ord_to_str(ord, &mut path).unwrap_or(false) {
+                out.hits.entry(path.clone()).or_default().merge(hit);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::query::{AllQuery, TermQuery};
+    use tantivy::schema::{IndexRecordOption, Schema, Value, FAST, STORED, STRING, TEXT};
+    use tantivy::{doc, Index, Term};
+
+    #[test]
+    fn test_file_group_limit() {
+        let mut schema_builder = Schema::builder();
+        let path = schema_builder.add_text_field("file_path", STRING | FAST);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        // 第 i 个文件包含 i 次查询词，得分随 i 增大
+        for i in 1..=10 {
+            let text = vec!["report"; i].join(" ") + " filler text here";
+            writer
+                .add_document(doc!(path => format!("/f{:02}", i), body => text))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(body, "report"),
+            IndexRecordOption::WithFreqs,
+        );
+        let groups = searcher
+            .search(&query, &FileGroupCollector::with_limit(3))
+            .unwrap();
+        // 总数统计全部命中的文件，不受上限影响
+        assert_eq!(groups.total(), 10);
+        let mut paths: Vec<String> = groups.hits.into_keys().collect();
+        paths.sort();
+        assert_eq!(paths, vec!["/f08", "/f09", "/f10"]);
+    }
+
+    #[test]
+    fn test_file_group_limit_across_segments() {
+        let mut schema_builder = Schema::builder();
+        let path = schema_builder.add_text_field("file_path", STRING | FAST);
+        let passage_no = schema_builder.add_u64_field("passage_no", FAST);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        writer.set_merge_policy(Box::new(tantivy::indexer::NoMergePolicy));
+        // /big 的父文档和段落在不同的段中，单独的父文档或段落得分都低于 /other，合计高于 /other
+        writer
+            .add_document(doc!(path => "/other", body => "report report report filler"))
+            .unwrap();
+        writer
+            .add_document(doc!(path => "/big", body => "report report filler filler"))
+            .unwrap();
+        writer.commit().unwrap();
+        writer
+            .add_document(doc!(
+                path => "/big",
+                passage_no => 1u64,
+                body => "report report filler filler"
+            ))
+            .unwrap();
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        let query = TermQuery::new(
+            Term::from_field_text(body, "report"),
+            IndexRecordOption::WithFreqs,
+        );
+        let groups = searcher
+            .search(&query, &FileGroupCollector::with_limit(1))
+            .unwrap();
+        assert_eq!(groups.total(), 2);
+        let hit = &groups.hits["/big"];
+        assert_eq!(groups.hits.len(), 1);
+        assert!(hit.doc.is_some() && hit.passage.is_some());
+    }
+
+    #[test]
+    fn test_file_group_sort_limit() {
+        let mut schema_builder = Schema::builder();
+        let path = schema_builder.add_text_field("file_path", STRING | FAST);
+        let modified = schema_builder.add_i64_field("modified_time", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        for i in 1..=10i64 {
+            writer
+                .add_document(doc!(path => format!("/f{:02}", i), modified => i * 1000))
+                .unwrap();
+        }
+        writer.add_document(doc!(path => "/z-no-time")).unwrap();
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        // 空查询的得分都相同，按修改时间选出最新的文件，而不是任意的文件
+        let top = |sort: SearchSort| {
+            let groups = searcher
+                .search(
+                    &AllQuery,
+                    &FileGroupCollector::with_limit(3).with_sort(sort),
+                )
+                .unwrap();
+            let mut paths: Vec<String> = groups.hits.into_keys().collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(top(SearchSort::Newest), vec!["/f08", "/f09", "/f10"]);
+        assert_eq!(top(SearchSort::Oldest), vec!["/f01", "/f02", "/f03"]);
+        assert_eq!(top(SearchSort::Name), vec!["/f01", "/f02", "/f03"]);
+    }
+
+    #[test]
+    fn test_facet_filters() {
+        let mut schema_builder = Schema::builder();
+        let path = schema_builder.add_text_field("file_path", STRING | FAST);
+        let file_type = schema_builder.add_text_field("file_type", STRING | FAST);
+        let modified = schema_builder.add_i64_field("modified_time", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        for (p, t, m) in [
+            ("/r/a/1.pdf", "pdf", 100i64),
+            ("/r/a/2.txt", "txt", 200),
+            ("/r/b/3.pdf", "pdf", 300),
+        ] {
+            writer
+                .add_document(doc!(path => p, file_type => t, modified => m))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let filters = FacetFilters {
+            file_types: Some(HashSet::from(["pdf".to_string()])),
+            dirs: Some(vec![Regex::new("^/r/a$").unwrap()]),
+            modified: None,
+        };
+        let groups = searcher
+            .search(
+                &AllQuery,
+                &FileGroupCollector::with_limit(10).with_facets(Some(filters)),
+            )
+            .unwrap();
+        // 只有同时满足文件类型和目录条件的文件计入结果，其余命中文件保留分面取值
+        assert_eq!(groups.total(), 1);
+        assert_eq!(groups.hits.keys().collect::<Vec<_>>(), vec!["/r/a/1.pdf"]);
+        assert_eq!(groups.matched.len(), 3);
+        let mut flags: Vec<(Option<String>, bool, bool)> = groups
+            .matched
+            .values()
+            .map(|f| (f.file_type.clone(), f.type_ok, f.dir_ok))
+            .collect();
+        flags.sort();
+        assert_eq!(
+            flags,
+            vec![
+                (Some("pdf".to_string()), true, false),
+                (Some("pdf".to_string()), true, true),
+                (Some("txt".to_string()), false, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_doc_filter() {
+        let mut schema_builder = Schema::builder();
+        let path = schema_builder.add_text_field("file_path", STRING | FAST);
+        let passage_no = schema_builder.add_u64_field("passage_no", FAST | STORED);
+        let body = schema_builder.add_text_field("body", TEXT | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        // 得分最高的段落不满足筛选条件，应选用满足条件的段落
+        writer
+            .add_document(doc!(path => "/a", passage_no => 1u64, body => "report report report"))
+            .unwrap();
+        writer
+            .add_document(doc!(path => "/a", passage_no => 2u64, body => "report ERR-1234 and more words"))
+            .unwrap();
+        writer
+            .add_document(doc!(path => "/b", body => "report"))
+            .unwrap();
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(body, "report"),
+            IndexRecordOption::WithFreqs,
+        );
+        let filter: DocFilter = Arc::new(move |d: &TantivyDocument| {
+            d.get_all(body)
+                .filter_map(|v| v.as_str())
+                .any(|s| s.contains("ERR-"))
+        });
+        let groups = searcher
+            .search(&query, &FileGroupCollector::with_limit(10).with_filter(Some(filter)))
+            .unwrap();
+        assert_eq!(groups.total(), 1);
+        let hits = groups.hits;
+        assert_eq!(hits.len(), 1);
+        let hit = &hits["/a"];
+        assert_eq!(hit.passage_hits, 1);
+        let addr = hit.passage.unwrap().1;
+        let d: TantivyDocument = searcher.doc(addr).unwrap();
+        assert_eq!(d.get_first(passage_no).and_then(|v| v.as_u64()), Some(2));
+    }
+}
@@ -0,0 +1,144 @@
+use chardetng::EncodingDetector;
+use std::{fs, path::PathBuf};
+
+/// CSV/TSV 文件解析结果
+#[derive(Default, Debug)]
+pub struct CsvDoc {
+    /// 每行一条记录，单元格以制表符分隔，行号与源文件一致（第 1 条记录为表头）
+    pub text: String,
+    /// 数据行，每行为（表头、单元格）列表，表头已转为小写
+    pub rows: Vec<Vec<(String, String)>>,
+}
+
+/// 读取 CSV/TSV 文件，自动识别文本编码和分隔符
+pub fn read_csv(path: &PathBuf) -> CsvDoc {
+    let Ok(bytes) = fs::read(path) else {
+        return CsvDoc::default();
+    };
+    let text = decode_text(&bytes);
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let delimiter = if ext == "tsv" {
+        b'\t'
+    } else {
+        sniff_delimiter(&text)
+    };
+    parse_csv(&text, delimiter)
+}
+
+/// 识别文本编码（含 BOM、UTF-8、GBK 等）并解码为字符串
+fn decode_text(bytes: &[u8]) -> String {
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    let (decoded, _, _) = encoding.decode(bytes);
+    decoded.into_owned()
+}
+
+/// 根据前几行中各候选分隔符出现次数的一致性推断分隔符，默认为逗号
+fn sniff_delimiter(text: &str) -> u8 {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(10)
+        .collect();
+    let mut best = (b',', 0usize);
+    for cand in [b',', b'\t', b';', b'|'] {
+        let min_count = lines
+            .iter()
+            .map(|l| l.bytes().filter(|b| *b == cand).count())
+            .min()
+            .unwrap_or(0);
+        if min_count > best.1 {
+            best = (cand, min_count);
+        }
+    }
+    best.0
+}
+
+fn parse_csv(text: &str, delimiter: u8) -> CsvDoc {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut doc = CsvDoc::default();
+    let mut headers: Vec<String> = Vec::new();
+    let mut lines = 0u64;
+    for (i, record) in reader.records().flatten().enumerate() {
+        // 空行和跨行的单元格补齐为空行，单元格内的换行和制表符替换为空格，使内容的第 n 行即源文件的第 n 行
+        // 记录的位置从其前面被跳过的空行算起
+        let line = record.position().map_or(0, |p| {
+            let skipped = text.as_bytes()[p.byte() as usize..]
+                .iter()
+                .take_while(|b| matches!(b, b'\r' | b'\n'))
+                .filter(|b| **b == b'\n')
+                .count();
+            p.line() + skipped as u64
+        });
+        while lines + 1 < line {
+            doc.text.push('\n');
+            lines += 1;
+        }
+        let cells: Vec<String> = record
+            .iter()
+            .map(|c| c.trim().replace(['\r', '\n', '\t'], " "))
+            .collect();
+        doc.text.push_str(&cells.join("\t"));
+        doc.text.push('\n');
+        lines += 1;
+        if i == 0 {
+            headers = cells.iter().map(|h| h.to_lowercase()).collect();
+            continue;
+        }
+        let row = cells
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_empty())
+            .filter_map(|(j, c)| {
+                let h = headers.get(j).filter(|h| !h.is_empty())?;
+                Some((h.clone(), c.to_string()))
+            })
+            .collect();
+        doc.rows.push(row);
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_delimiter() {
+        assert_eq!(sniff_delimiter("a,b,c\n1,2,3\n"), b',');
+        assert_eq!(sniff_delimiter("a;b;c\n1;2,5;3\n"), b';');
+        assert_eq!(sniff_delimiter("a\tb\n1\t2\n"), b'\t');
+    }
+
+    #[test]
+    fn test_read_csv_gbk() {
+        let tmp = std::env::temp_dir().join("test_csv_read.csv");
+        let (bytes, _, _) = encoding_rs::GBK.encode("Customer;金额\n张三;100\n李四;200\n");
+        fs::write(&tmp, &bytes).unwrap();
+        let doc = read_csv(&tmp);
+        assert_eq!(doc.rows.len(), 2);
+        assert_eq!(doc.rows[0][0], ("customer".to_string(), "张三".to_string()));
+        assert_eq!(doc.rows[1][1], ("金额".to_string(), "200".to_string()));
+        assert!(doc.text.starts_with("Customer\t金额\n"));
+        let _ = fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_source_lines() {
+        let doc = parse_csv("name,note\n\n张三,\"多行\n备注\"\n李四,ok\n", b',');
+        assert_eq!(doc.rows.len(), 2);
+        let lines: Vec<&str> = doc.text.lines().collect();
+        assert_eq!(lines[0], "name\tnote");
+        assert_eq!(lines[2], "张三\t多行 备注");
+        assert_eq!(lines[4], "李四\tok");
+    }
+}
@@ -0,0 +1,205 @@
+use std::io::Read;
+use std::{fs, path::PathBuf};
+use zip::ZipArchive;
+
+/// 按文件头部签名识别出的文件类型
+#[derive(Debug, PartialEq)]
+pub struct DetectedType {
+    /// 用于选择解析器的扩展名：识别出的规范扩展名，无法识别时为原扩展名
+    pub ext: String,
+    /// 写入 file_type 的扩展名：原扩展名为空或与实际格式不符（如以 .jpg 命名的 PDF）时才使用识别出的扩展名
+    pub file_type: String,
+    pub mime: Option<&'static str>,
+}
+
+/// 以 TIFF 为容器的相机 RAW 格式
+const TIFF_EXTS: &[&str] = &[
+    "tif", "tiff", "dng", "nef", "nrw", "cr2", "arw", "sr2", "srw", "pef", "erf", "kdc", "dcr",
+    "3fr", "iiq", "mos",
+];
+
+/// 签名（偏移、字节）、MIME 类型，以及与该类型相符的扩展名（第一个为规范扩展名）
+const SIGNATURES: &[(usize, &[u8], &str, &[&str])] = &[
+    (0, b"%PDF-", "application/pdf", &["pdf"]),
+    (0, b"\xFF\xD8\xFF", "image/jpeg", &["jpg", "jpeg"]),
+    (0, b"\x89PNG\r\n\x1a\n", "image/png", &["png"]),
+    (0, b"GIF87a", "image/gif", &["gif"]),
+    (0, b"GIF89a", "image/gif", &["gif"]),
+    (0, b"II*\0", "image/tiff", TIFF_EXTS),
+    (0, b"MM\0*", "image/tiff", TIFF_EXTS),
+    (0, b"ID3", "audio/mpeg", &["mp3"]),
+    (0, b"fLaC", "audio/flac", &["flac"]),
+    (0, b"OggS", "audio/ogg", &["ogg", "oga", "opus"]),
+    (0, b"\x1A\x45\xDF\xA3", "video/x-matroska", &["mkv", "webm"]),
+    (8, b"WEBP", "image/webp", &["webp"]),
+    (8, b"WAVE", "audio/wav", &["wav"]),
+    (8, b"heic", "image/heic", &["heic", "heif"]),
+    (8, b"heix", "image/heic", &["heic", "heif"]),
+    (8, b"mif1", "image/heif", &["heif", "heic", "avif"]),
+    (8, b"avif", "image/avif", &["avif"]),
+    (8, b"avis", "image/avif", &["avif"]),
+    (8, b"M4A ", "audio/mp4", &["m4a"]),
+    (8, b"qt  ", "video/quicktime", &["mov"]),
+    (4, b"ftyp", "video/mp4", &["mp4", "m4v", "m4a", "mov"]),
+];
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const OLE_MAGIC: &[u8] = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1";
+
+/// 根据文件头部签名识别文件类型，不依赖扩展名
+///
+/// 解析器按识别出的规范扩展名选择；file_type 在识别结果与扩展名相符时保留原扩展名（如 `.jpeg`、RAW 格式的 `.nef`），
+/// 不符时使用识别出的规范扩展名；无法识别的 zip/OLE 容器（如 epub、msi）和文本文件保留原扩展名，没有扩展名的文本文件视为 txt
+pub fn detect_file_type(path: &PathBuf, ext: &str) -> DetectedType {
+    let mut header = Vec::with_capacity(4096);
+    if let Ok(f) = fs::File::open(path) {
+        let _ = f.take(4096).read_to_end(&mut header);
+    }
+    let resolve = |mime: &'static str, exts: &[&str]| DetectedType {
+        ext: exts[0].to_string(),
+        file_type: if exts.contains(&ext) { ext } else { exts[0] }.to_string(),
+        mime: Some(mime),
+    };
+    let keep = |mime: Option<&'static str>, fallback: &str| {
+        let ext = if ext.is_empty() { fallback } else { ext };
+        DetectedType {
+            ext: ext.to_string(),
+            file_type: ext.to_string(),
+            mime,
+        }
+    };
+    if header.starts_with(ZIP_MAGIC) {
+        return match zip_office_type(path) {
+            Some((mime, exts)) => resolve(mime, exts),
+            None => keep(Some("application/zip"), "zip"),
+        };
+    }
+    if header.starts_with(OLE_MAGIC) {
+        return match ole_office_type(path) {
+            Some((mime, exts)) => resolve(mime, exts),
+            None => keep(Some("application/x-ole-storage"), ""),
+        };
+    }
+    for (offset, magic, mime, exts) in SIGNATURES {
+        if header.get(*offset..*offset + magic.len()) == Some(*magic) {
+            return resolve(mime, exts);
+        }
+    }
+    if looks_like_text(&header) {
+        return keep(Some("text/plain"), "txt");
+    }
+    keep(None, "")
+}
+
+/// Office Open XML 文档按 zip 中的目录区分 Word、Excel、PowerPoint
+fn zip_office_type(path: &PathBuf) -> Option<(&'static str, &'static [&'static str])> {
+    let zip = ZipArchive::new(fs::File::open(path).ok()?).ok()?;
+    let has_dir = |prefix: &str| zip.file_names().any(|n| n.starts_with(prefix));
+    if has_dir("word/") {
+        Some((
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            &["docx"],
+        ))
+    } else if has_dir("xl/") {
+        Some((
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            &["xlsx"],
+        ))
+    } else if has_dir("ppt/") {
+        Some((
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            &["pptx"],
+        ))
+    } else {
+        None
+    }
+}
+
+/// 旧版 Office 文档（OLE 复合文档）按其中的流名称区分 Word、Excel、PowerPoint
+fn ole_office_type(path: &PathBuf) -> Option<(&'static str, &'static [&'static str])> {
+    let cfb = cfb::open(path).ok()?;
+    if cfb.exists("/WordDocument") {
+        Some(("application/msword", &["doc"]))
+    } else if cfb.exists("/Workbook") || cfb.exists("/Book") {
+        Some(("application/vnd.ms-excel", &["xls"]))
+    } else if cfb.exists("/PowerPoint Document") {
+        Some(("application/vnd.ms-powerpoint", &["ppt"]))
+    } else {
+        None
+    }
+}
+
+/// 文件头部不含 NUL 且是合法的 UTF-8（末尾可能截断了一个字符）时视为文本
+fn looks_like_text(header: &[u8]) -> bool {
+    if header.is_empty() || header.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(header) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_detect_renamed_docx() {
+        let tmp = std::env::temp_dir().join("test_detect_renamed.doc");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&tmp).unwrap());
+        zip.start_file("word/document.xml", zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(b"<w:document/>").unwrap();
+        zip.finish().unwrap();
+        let t = detect_file_type(&tmp, "doc");
+        assert_eq!((t.ext.as_str(), t.file_type.as_str()), ("docx", "docx"));
+        assert!(t.mime.unwrap().contains("wordprocessingml"));
+        let _ = fs::remove_file(&tmp);
+    }
+
+    fn detect_bytes(name: &str, ext: &str, bytes: &[u8]) -> DetectedType {
+        let p = std::env::temp_dir().join(name);
+        fs::write(&p, bytes).unwrap();
+        let t = detect_file_type(&p, ext);
+        let _ = fs::remove_file(&p);
+        t
+    }
+
+    #[test]
+    fn test_detect_signatures() {
+        let t = detect_bytes("test_detect_a.jpeg", "jpeg", b"\xFF\xD8\xFF\xE0\0\x10JFIF");
+        assert_eq!((t.ext.as_str(), t.mime), ("jpg", Some("image/jpeg")));
+        assert_eq!(t.file_type, "jpeg");
+        let t = detect_bytes("test_detect_b", "", b"%PDF-1.7\n");
+        assert_eq!((t.ext.as_str(), t.mime), ("pdf", Some("application/pdf")));
+        let t = detect_bytes("test_detect_c", "", "纯文本内容".as_bytes());
+        assert_eq!((t.ext.as_str(), t.mime), ("txt", Some("text/plain")));
+        let t = detect_bytes("test_detect_d.md", "md", b"# title\n");
+        assert_eq!((t.ext.as_str(), t.mime), ("md", Some("text/plain")));
+        // 以 .jpg 命名的 PDF 按实际格式记录
+        let t = detect_bytes("test_detect_e.jpg", "jpg", b"%PDF-1.4\n");
+        assert_eq!((t.ext.as_str(), t.file_type.as_str()), ("pdf", "pdf"));
+    }
+
+    #[test]
+    fn test_detect_raw_and_avif() {
+        // RAW 格式以 TIFF 为容器，按 TIFF 读取元数据，file_type 保留原扩展名
+        for (name, ext, bytes) in [
+            ("test_detect.nef", "nef", &b"MM\0*\0\0\0\x08"[..]),
+            ("test_detect.dng", "dng", &b"II*\0\x08\0\0\0"[..]),
+            ("test_detect.cr2", "cr2", &b"II*\0\x10\0\0\0CR\x02\0"[..]),
+        ] {
+            let t = detect_bytes(name, ext, bytes);
+            assert_eq!((t.ext.as_str(), t.file_type.as_str()), ("tif", ext));
+            assert_eq!(t.mime, Some("image/tiff"));
+        }
+        let avif = b"\0\0\0\x1cftypavif\0\0\0\0avifmif1miaf";
+        let t = detect_bytes("test_detect_f.avif", "avif", avif);
+        assert_eq!((t.ext.as_str(), t.file_type.as_str()), ("avif", "avif"));
+        assert_eq!(t.mime, Some("image/avif"));
+        let t = detect_bytes("test_detect_g", "", avif);
+        assert_eq!(t.file_type, "avif");
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::media_meta::{AUDIO_EXTS, VIDEO_EXTS};
+
+/// 文件类型分组，筛选时可用分组 id 代替一组扩展名
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTypeGroup {
+    pub id: String,
+    /// 显示名称，内置分组为空，由前端按 id 本地化
+    #[serde(default)]
+    pub name: Option<String>,
+    pub extensions: Vec<String>,
+    /// 是否为内置分组，设置中的自定义分组总是为 false
+    #[serde(default, skip_deserializing)]
+    pub builtin: bool,
+}
+
+/// 内置的文件类型分组
+const BUILTIN_GROUPS: &[(&str, &[&str])] = &[
+    ("documents", &["txt", "md", "pdf", "doc", "docx", "rtf", "odt"]),
+    ("spreadsheets", &["xls", "xlsx", "csv", "tsv", "ods"]),
+    ("presentations", &["ppt", "pptx", "odp"]),
+    (
+        "code",
+        &[
+            "rs", "js", "jsx", "mjs", "cjs", "ts", "tsx", "py", "go", "java", "c", "h", "cpp",
+            "hpp", "json", "toml", "yaml", "yml",
+        ],
+    ),
+    (
+        "images",
+        &[
+            "jpg", "jpeg", "png", "gif", "bmp", "tif", "tiff", "heic", "heif", "avif", "webp",
+            "svg", "dng", "nef", "cr2", "arw",
+        ],
+    ),
+    ("audio", AUDIO_EXTS),
+    ("video", VIDEO_EXTS),
+    ("archives", &["zip", "tar", "gz", "tgz", "bz2", "xz", "7z", "rar"]),
+    ("logs", &["log"]),
+];
+
+/// 合并内置分组与设置中的自定义分组：id 与内置分组相同时追加扩展名，否则作为新分组
+pub fn file_type_groups(custom: &[FileTypeGroup]) -> Vec<FileTypeGroup> {
+    let mut groups: Vec<FileTypeGroup> = BUILTIN_GROUPS
+        .iter()
+        .map(|(id, exts)| FileTypeGroup {
+            id: id.to_string(),
+            name: None,
+            extensions: exts.iter().map(|e| e.to_string()).collect(),
+            builtin: true,
+        })
+        .collect();
+    for c in custom {
+        let id = c.id.trim().to_lowercase();
+        if id.is_empty() {
+            continue;
+        }
+        // 扩展名统一为小写、去掉开头的点
+        let exts = c
+            .extensions
+            .iter()
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty());
+        let group = match groups.iter_mut().position(|g| g.id == id) {
+            Some(i) => &mut groups[i],
+            None => {
+                groups.push(FileTypeGroup {
+                    id,
+                    name: c.name.clone(),
+                    extensions: Vec::new(),
+                    builtin: false,
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+        for e in exts {
+            if !group.extensions.contains(&e) {
+                group.extensions.push(e);
+            }
+        }
+    }
+    groups
+}
+
+/// 将筛选中的文件类型展开为扩展名：分组 id 展开为该分组的扩展名，其余按扩展名处理
+pub fn expand_file_types(file_types: &[String], groups: &[FileTypeGroup]) -> Vec<String> {
+    let mut expanded: Vec<String> = Vec::new();
+    for ft in file_types {
+        let ft = ft.trim().trim_start_matches('.').to_lowercase();
+        match groups.iter().find(|g| g.id == ft) {
+            Some(g) => expanded.extend(g.extensions.iter().cloned()),
+            None => expanded.push(ft),
+        }
+    }
+    expanded.sort();
+    expanded.dedup();
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_groups_merge() {
+        let custom: Vec<FileTypeGroup> = serde_json::from_str(
+            r#"[{"id": "Code", "extensions": [".kt", "rs"]},
+                {"id": "ebooks", "name": "电子书", "extensions": ["EPUB", "mobi"]}]"#,
+        )
+        .unwrap();
+        let groups = file_type_groups(&custom);
+        let code = groups.iter().find(|g| g.id == "code").unwrap();
+        assert!(code.builtin);
+        assert_eq!(code.extensions.iter().filter(|e| *e == "rs").count(), 1);
+        assert!(code.extensions.contains(&"kt".to_string()));
+        let ebooks = groups.last().unwrap();
+        assert_eq!(ebooks.id, "ebooks");
+        assert_eq!(ebooks.name.as_deref(), Some("电子书"));
+        assert_eq!(ebooks.extensions, vec!["epub", "mobi"]);
+        assert!(!ebooks.builtin);
+    }
+
+    #[test]
+    fn test_expand_file_types() {
+        let groups = file_type_groups(&[]);
+        let exts = expand_file_types(&["spreadsheets".to_string(), ".MD".to_string()], &groups);
+        assert_eq!(exts, vec!["csv", "md", "ods", "tsv", "xls", "xlsx"]);
+    }
+}
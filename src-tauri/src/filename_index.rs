@@ -0,0 +1,565 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::columnar::StrColumn;
+use tantivy::query::{BooleanQuery, BoostQuery, Occur, Query, RegexQuery};
+use tantivy::schema::{Field, Schema, Value, FAST, STORED, STRING};
+use tantivy::{DocAddress, DocId, Score, SegmentOrdinal, SegmentReader, TantivyDocument};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::index_store::{build_index_version, live_index_dir, RebuildState};
+use crate::indexer::{app_index_dir, walk_files};
+use crate::types::{
+    DirectoryConfigCmd, FilenameIndexProgressPayload, FilenameResultPayload,
+    FilenameSearchResponsePayload,
+};
+use crate::unicode_norm::nfc;
+use crate::zh_normalize::normalize_zh;
+
+/// 文件名以查询词开头时的加权，排在只包含查询词的文件名之前
+const PREFIX_BOOST: f32 = 2.0;
+
+/// 子串匹配键的最大长度（字符数），每个文件名的词项总长随文件名长度线性增长；
+/// 更长的查询词先按前这些字符匹配候选文件，再在文件名的匹配键中确认
+const MAX_SUFFIX_CHARS: usize = 8;
+
+/// 文件名索引目录，与内容索引并列
+pub fn filename_index_dir(app: &AppHandle) -> PathBuf {
+    app_index_dir(app).with_file_name("filenames")
+}
+
+/// 文件名索引的重建状态，由应用统一管理，同一时间只允许一次重建
+#[derive(Default)]
+pub struct FilenameIndexState {
+    rebuild: RebuildState,
+}
+
+/// 文件名索引的 schema：文件名和完整路径的原文（展示、打开文件），文件名的匹配键（精确词项，按前缀匹配；
+/// 快速字段，确认较长的查询词）及其从每个字符开始的后缀（精确词项，子串匹配转为在后缀上按前缀匹配），
+/// 以及所在各级目录名的匹配键
+///
+/// 只为文件名生成后缀，路径中的目录按目录名整体索引：不同的目录名远少于文件数，按目录名匹配时遍历词典的开销很小
+fn build_schema() -> Schema {
+    let mut schema_builder = Schema::builder();
+    schema_builder.add_text_field("name", STORED);
+    schema_builder.add_text_field("name_key", STRING | FAST);
+    schema_builder.add_text_field("name_suffix", STRING);
+    schema_builder.add_text_field("file_path", STORED);
+    schema_builder.add_text_field("dirs", STRING);
+    schema_builder.add_text_field("file_type", STORED);
+    schema_builder.add_u64_field("file_size", STORED);
+    schema_builder.add_i64_field("modified_time", STORED);
+    schema_builder.build()
+}
+
+/// 文件名索引中的字段
+#[derive(Clone, Copy)]
+struct FilenameFields {
+    name: Field,
+    name_key: Field,
+    name_suffix: Field,
+    file_path: Field,
+    dirs: Field,
+    file_type: Field,
+    file_size: Field,
+    modified_time: Field,
+}
+
+fn filename_fields(schema: &Schema) -> Result<FilenameFields, String> {
+    let field = |name: &str| {
+        schema.get_field(name).map_err(|_| {
+            format!(
+                "filename index schema outdated, missing field `{}`, please rebuild index",
+                name
+            )
+        })
+    };
+    Ok(FilenameFields {
+        name: field("name")?,
+        name_key: field("name_key")?,
+        name_suffix: field("name_suffix")?,
+        file_path: field("file_path")?,
+        dirs: field("dirs")?,
+        file_type: field("file_type")?,
+        file_size: field("file_size")?,
+        modified_time: field("modified_time")?,
+    })
+}
+
+/// 匹配键：NFC、繁简和全半角归一、分隔符统一为 `/`、小写，索引和查询使用相同的规则
+fn match_key(text: &str) -> String {
+    normalize_zh(&nfc(text)).replace('\\', "/").to_lowercase()
+}
+
+/// 匹配键从每个字符开始的后缀，截取前 `MAX_SUFFIX_CHARS` 个字符，去重
+fn suffixes(key: &str) -> HashSet<&str> {
+    key.char_indices()
+        .map(|(i, _)| {
+            let end = key[i..]
+                .char_indices()
+                .nth(MAX_SUFFIX_CHARS)
+                .map(|(n, _)| i + n)
+                .unwrap_or(key.len());
+            &key[i..end]
+        })
+        .collect()
+}
+
+/// 路径匹配键中的各级目录名（不含文件名），去重
+fn dir_names(path_key: &str) -> HashSet<&str> {
+    let mut parts: Vec<&str> = path_key.split('/').filter(|p| !p.is_empty()).collect();
+    parts.pop();
+    parts.into_iter().collect()
+}
+
+/// 查询词截取为子串匹配键的最大长度
+fn truncate_key(word: &str) -> &str {
+    match word.char_indices().nth(MAX_SUFFIX_CHARS) {
+        Some((n, _)) => &word[..n],
+        None => word,
+    }
+}
+
+/// 文件名搜索的收集器：按得分取第 `offset` 个起的 `limit` 个文件并统计总数
+///
+/// 超过子串匹配键长度的查询词（`long_words`）只按前缀匹配了候选文件，收集时再确认文件名的匹配键包含完整的查询词，
+/// 总数只统计确认后的文件
+struct FilenameCollector {
+    long_words: Arc<Vec<String>>,
+    limit: usize,
+    offset: usize,
+}
+
+struct FilenameSegmentCollector {
+    segment_ord: SegmentOrdinal,
+    long_words: Arc<Vec<String>>,
+    name_keys: Option<StrColumn>,
+    keep: usize,
+    hits: Vec<(Score, DocAddress)>,
+    count: usize,
+}
+
+/// 按得分从高到低排列，得分相同时按文档地址
+fn sort_hits(hits: &mut [(Score, DocAddress)]) {
+    hits.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+}
+
+impl Collector for FilenameCollector {
+    type Fruit = (Vec<(Score, DocAddress)>, usize);
+    type Child = FilenameSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_local_id: SegmentOrdinal,
+        reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        let name_keys = if self.long_words.is_empty() {
+            None
+        } else {
+            Some(reader.fast_fields().str("name_key")?.ok_or_else(|| {
+                tantivy::TantivyError::SchemaError(
+                    "filename index schema outdated, please rebuild index".to_string(),
+                )
+            })?)
+        };
+        Ok(FilenameSegmentCollector {
+            segment_ord: segment_local_id,
+            long_words: self.long_words.clone(),
+            name_keys,
+            keep: self.offset + self.limit,
+            hits: Vec::new(),
+            count: 0,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, fruits: Vec<Self::Fruit>) -> tantivy::Result<Self::Fruit> {
+        let mut hits = Vec::new();
+        let mut count = 0;
+        for (segment_hits, segment_count) in fruits {
+            hits.extend(segment_hits);
+            count += segment_count;
+        }
+        sort_hits(&mut hits);
+        Ok((
+            hits.into_iter().skip(self.offset).take(self.limit).collect(),
+            count,
+        ))
+    }
+}
+
+impl SegmentCollector for FilenameSegmentCollector {
+    type Fruit = (Vec<(Score, DocAddress)>, usize);
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        if let Some(column) = &self.name_keys {
+            let mut key = String::new();
+            let found = column
+                .ords()
+                .first(doc)
+                .is_some_and(|ord| column.ord_to_str(ord, &mut key).unwrap_or(false));
+            if !found || !self.long_words.iter().all(|w| key.contains(w.as_str())) {
+                return;
+            }
+        }
+        self.count += 1;
+        self.hits.push((score, DocAddress::new(self.segment_ord, doc)));
+        // 段内只需保留前 `keep` 个，文件之间互不影响，截断后合并的结果不变
+        if self.hits.len() >= 2 * self.keep.max(1) {
+            sort_hits(&mut self.hits);
+            self.hits.truncate(self.keep);
+        }
+    }
+
+    fn harvest(mut self) -> Self::Fruit {
+        sort_hits(&mut self.hits);
+        self.hits.truncate(self.keep);
+        (self.hits, self.count)
+    }
+}
+
+/// 遍历索引目录重建文件名索引：只读取文件名、路径和元数据，不提取内容，不受文件类型限制
+///
+/// 新索引建在新的版本目录中，完成后再切换，重建期间仍可搜索原索引（见 `build_index_version`）
+///
+/// 每索引 1000 个文件回调一次已索引的文件数，返回索引的文件总数
+fn build_filename_index(
+    index_dir: &Path,
+    directories: Vec<DirectoryConfigCmd>,
+    progress: impl FnMut(usize),
+) -> Result<usize, String> {
+    build_index_version(index_dir, |dir| {
+        write_filename_index(dir, directories, progress)
+    })
+}
+
+/// 在 `index_dir` 中新建文件名索引并写入遍历到的所有文件
+fn write_filename_index(
+    index_dir: &Path,
+    directories: Vec<DirectoryConfigCmd>,
+    mut progress: impl FnMut(usize),
+) -> Result<usize, String> {
+    fs::create_dir_all(index_dir).map_err(|e| format!("create index dir error: {}", e))?;
+    let schema = build_schema();
+    let index = tantivy::Index::create_in_dir(index_dir, schema.clone())
+        .map_err(|e| format!("create index error: {}", e))?;
+    let fields = filename_fields(&schema)?;
+    let mut writer: tantivy::IndexWriter = index
+        .writer(50_000_000)
+        .map_err(|e| format!("writer error: {}", e))?;
+    let mut indexed = 0usize;
+    let mut error: Option<String> = None;
+    walk_files(directories, |path, meta, _| {
+        if error.is_some() {
+            return;
+        }
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .map(|e| chrono::Utc::now().timestamp_millis() - e.as_millis() as i64)
+            .unwrap_or(0);
+        let mut doc = TantivyDocument::default();
+        let name_key = match_key(&name);
+        let path_key = match_key(&path.to_string_lossy());
+        for suffix in suffixes(&name_key) {
+            doc.add_text(fields.name_suffix, suffix);
+        }
+        for dir in dir_names(&path_key) {
+            doc.add_text(fields.dirs, dir);
+        }
+        doc.add_text(fields.name_key, name_key);
+        doc.add_text(fields.name, nfc(&name));
+        doc.add_text(fields.file_path, path.to_string_lossy());
+        doc.add_text(fields.file_type, ext);
+        doc.add_u64(fields.file_size, meta.len());
+        doc.add_i64(fields.modified_time, modified);
+        if let Err(e) = writer.add_document(doc) {
+            error = Some(format!("add document error: {}", e));
+            return;
+        }
+        indexed += 1;
+        if indexed % 1000 == 0 {
+            progress(indexed);
+        }
+    });
+    if let Some(e) = error {
+        return Err(e);
+    }
+    writer
+        .commit()
+        .map_err(|e| format!("commit error: {}", e))?;
+    // 等待合并完成并释放索引文件，之后才能切换并删除原索引
+    writer
+        .wait_merging_threads()
+        .map_err(|e| format!("commit error: {}", e))?;
+    Ok(indexed)
+}
+
+/// 重建文件名索引：后台线程遍历目录，通过 `filename-index-progress` 事件通知前端进度，失败时事件中带有错误信息
+///
+/// 已有重建在进行时返回错误，不再启动新的重建
+pub fn do_rebuild_filename_index(
+    app: AppHandle,
+    directories: Vec<DirectoryConfigCmd>,
+) -> Result<(), String> {
+    let Some(guard) = app.state::<FilenameIndexState>().rebuild.try_start() else {
+        return Err("filename index rebuild already in progress".to_string());
+    };
+    thread::spawn(move || {
+        let emit = |is_indexing: bool, indexed: usize, last_updated: i64, error: Option<String>| {
+            let _ = app.emit(
+                "filename-index-progress",
+                FilenameIndexProgressPayload {
+                    is_indexing,
+                    indexed_files: indexed,
+                    last_updated,
+                    error,
+                },
+            );
+        };
+        emit(true, 0, 0, None);
+        let index_dir = filename_index_dir(&app);
+        let result = build_filename_index(&index_dir, directories, |n| emit(true, n, 0, None));
+        // 先清除重建状态再通知前端完成，前端收到通知后即可再次重建；重建中 panic 时 guard 同样清除重建状态
+        drop(guard);
+        match result {
+            Ok(indexed) => emit(false, indexed, chrono::Utc::now().timestamp_millis(), None),
+            Err(e) => emit(false, 0, 0, Some(e)),
+        }
+    });
+    Ok(())
+}
+
+/// 路径查询词（含 `/`）的匹配条件：按 `/` 切分后每一段都须匹配文件所在的某级目录名（不检查各段的先后顺序）
+///
+/// 两侧都有 `/` 的段须与目录名完全相同，第一段须是目录名的结尾，最后一段须是目录名或文件名的开头
+fn path_word_query(word: &str, fields: &FilenameFields) -> Result<Box<dyn Query>, String> {
+    let regex_query = |pattern: String, field: Field| -> Result<Box<dyn Query>, String> {
+        RegexQuery::from_pattern(&pattern, field)
+            .map(|q| Box::new(q) as Box<dyn Query>)
+            .map_err(|e| format!("parse query error: {}", e))
+    };
+    let segments: Vec<&str> = word.split('/').collect();
+    let last = segments.len() - 1;
+    let mut must: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+    for (i, seg) in segments.iter().enumerate() {
+        if seg.is_empty() {
+            continue;
+        }
+        let seg = regex::escape(seg);
+        let q = if i == last {
+            let should: Vec<(Occur, Box<dyn Query>)> = vec![
+                (Occur::Should, regex_query(format!("{}.*", seg), fields.dirs)?),
+                (Occur::Should, regex_query(format!("{}.*", seg), fields.name_key)?),
+            ];
+            Box::new(BooleanQuery::new(should))
+        } else if i == 0 {
+            regex_query(format!(".*{}", seg), fields.dirs)?
+        } else {
+            regex_query(seg, fields.dirs)?
+        };
+        must.push((Occur::Must, q));
+    }
+    Ok(Box::new(BooleanQuery::new(must)))
+}
+
+/// 在文件名索引中查询：按空白切分的每个词都须出现在文件名中（含 `/` 的词匹配所在目录，见 `path_word_query`），
+/// 忽略大小写、繁简和全半角，文件名以查询词开头的排在前面
+fn search_filename_index(
+    index_dir: &Path,
+    query: &str,
+    limit: usize,
+    offset: usize,
+) -> Result<FilenameSearchResponsePayload, String> {
+    let words: Vec<String> = query.split_whitespace().map(match_key).collect();
+    let index_dir = live_index_dir(index_dir);
+    if words.is_empty() || !index_dir.exists() {
+        return Ok(FilenameSearchResponsePayload {
+            results: vec![],
+            total_count: 0,
+        });
+    }
+    let index =
+        tantivy::Index::open_in_dir(index_dir).map_err(|e| format!("open index error: {}", e))?;
+    let fields = filename_fields(&index.schema())?;
+    let long_words: Vec<String> = words
+        .iter()
+        .filter(|w| !w.contains('/') && truncate_key(w).len() < w.len())
+        .cloned()
+        .collect();
+    let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+    for word in &words {
+        if word.contains('/') {
+            clauses.push((Occur::Must, path_word_query(word, &fields)?));
+            continue;
+        }
+        // 包含查询词即某个后缀以查询词开头，按前缀匹配只需遍历词典中的一段
+        let substring = RegexQuery::from_pattern(
+            &format!("{}.*", regex::escape(truncate_key(word))),
+            fields.name_suffix,
+        )
+        .map_err(|e| format!("parse query error: {}", e))?;
+        let prefix = RegexQuery::from_pattern(&format!("{}.*", regex::escape(word)), fields.name_key)
+            .map_err(|e| format!("parse query error: {}", e))?;
+        let should: Vec<(Occur, Box<dyn Query>)> = vec![
+            (Occur::Should, Box::new(substring)),
+            (
+                Occur::Should,
+                Box::new(BoostQuery::new(Box::new(prefix), PREFIX_BOOST)),
+            ),
+        ];
+        clauses.push((Occur::Must, Box::new(BooleanQuery::new(should))));
+    }
+    let reader = index.reader().map_err(|e| format!("reader error: {}", e))?;
+    let searcher = reader.searcher();
+    let collector = FilenameCollector {
+        long_words: Arc::new(long_words),
+        limit: limit.max(1),
+        offset,
+    };
+    let (top_docs, total_count) = searcher
+        .search(&BooleanQuery::new(clauses), &collector)
+        .map_err(|e| format!("search error: {}", e))?;
+    let mut results = Vec::new();
+    for (_, addr) in top_docs {
+        let doc: TantivyDocument = searcher
+            .doc(addr)
+            .map_err(|e| format!("doc read error: {}", e))?;
+        let text = |field: Field| {
+            doc.get_first(field)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        results.push(FilenameResultPayload {
+            name: text(fields.name),
+            file_path: text(fields.file_path),
+            file_type: text(fields.file_type),
+            file_size: doc
+                .get_first(fields.file_size)
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0),
+            modified_time: doc
+                .get_first(fields.modified_time)
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0),
+        });
+    }
+    Ok(FilenameSearchResponsePayload {
+        results,
+        total_count,
+    })
+}
+
+/// 文件名搜索，未建立文件名索引时返回空结果
+pub fn do_search_filenames(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<FilenameSearchResponsePayload, String> {
+    search_filename_index(
+        &filename_index_dir(&app),
+        &query,
+        limit.unwrap_or(50),
+        offset.unwrap_or(0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffixes() {
+        let mut s: Vec<&str> = suffixes("報告a").into_iter().collect();
+        s.sort();
+        assert_eq!(s, vec!["a", "告a", "報告a"]);
+        let long = "x".repeat(100);
+        assert!(suffixes(&long).iter().all(|s| s.len() <= MAX_SUFFIX_CHARS));
+        assert_eq!(truncate_key(&long).len(), MAX_SUFFIX_CHARS);
+        let mut dirs: Vec<&str> = dir_names("d:/docs/a/docs/b.txt").into_iter().collect();
+        dirs.sort();
+        assert_eq!(dirs, vec!["a", "d:", "docs"]);
+    }
+
+    #[test]
+    fn test_filename_index() {
+        let root = std::env::temp_dir().join("test_filename_index");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("報告/archive")).unwrap();
+        for name in [
+            "報告/Q3-Report#1.PDF",
+            "報告/archive/report_2023.bin",
+            "notes_report.txt",
+            "budget.xlsx",
+        ] {
+            fs::write(root.join(name), "x").unwrap();
+        }
+        let dir: DirectoryConfigCmd = serde_json::from_value(serde_json::json!({
+            "path": root.to_string_lossy(), "enabled": true, "recursive": true, "lastIndexed": 0
+        }))
+        .unwrap();
+        let index_dir = std::env::temp_dir().join("test_filename_index_db");
+        // 重建时切换到新版本并删除原版本
+        for _ in 0..2 {
+            assert_eq!(
+                build_filename_index(&index_dir, vec![dir.clone()], |_| {}).unwrap(),
+                4
+            );
+        }
+        let versions = fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with("test_filename_index_db-"))
+            .count();
+        assert_eq!(versions, 1);
+
+        let names = |q: &str| {
+            search_filename_index(&index_dir, q, 10, 0)
+                .unwrap()
+                .results
+                .into_iter()
+                .map(|r| r.name)
+                .collect::<Vec<_>>()
+        };
+        // 子串匹配，不区分大小写，文件名以查询词开头的排在前面
+        let hits = names("REPORT");
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0], "report_2023.bin");
+        assert_eq!(names("q3-report#1"), vec!["Q3-Report#1.PDF"]);
+        assert_eq!(names("bud"), vec!["budget.xlsx"]);
+        // 含 `/` 的词匹配路径，繁简写法互相匹配
+        assert_eq!(names("报告/archive report"), vec!["report_2023.bin"]);
+        assert_eq!(names("chive/rep"), vec!["report_2023.bin"]);
+        assert_eq!(names("/archive/"), vec!["report_2023.bin"]);
+        assert!(names("/chive/").is_empty());
+        assert!(names("missing").is_empty());
+        assert_eq!(names("port#"), vec!["Q3-Report#1.PDF"]);
+        // 超过子串匹配键长度的查询词在候选文件中确认完整匹配，总数也只统计确认的文件
+        assert_eq!(names("report_2023.bin"), vec!["report_2023.bin"]);
+        assert!(names("report_2024").is_empty());
+        let total = |q: &str| search_filename_index(&index_dir, q, 1, 0).unwrap().total_count;
+        assert_eq!(total("report"), 3);
+        assert_eq!(total("report_2024"), 0);
+        assert_eq!(total("q3-report#1.pdf"), 1);
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(live_index_dir(&index_dir));
+    }
+}
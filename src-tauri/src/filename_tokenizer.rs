@@ -0,0 +1,146 @@
+use tantivy::tokenizer::{Token, Tokenizer};
+
+use crate::mixed_tokenizer::{is_cjk, VecTokenStream};
+
+/// 单词最多输出的前缀长度（字符数），更长的单词只额外输出完整单词
+const MAX_PREFIX_CHARS: usize = 20;
+
+/// 文件名分词器：按下划线、连字符、点、空白等分隔符，以及大小写变化和字母数字交界切分单词，
+/// 如 `QuarterlyReport_2024-Q3.xlsx` 切分为 `Quarterly`、`Report`、`2024`、`Q`、`3`、`xlsx`；
+/// 连续的中日韩文字按 1-3 字符 n-gram 切分
+///
+/// 每个单词同时输出其前缀（如 `Rep`、`Repo`），输入单词开头部分也能匹配；
+/// 与 n-gram 分词器相同，所有词项的位置均为 0，查询时要求所有词项都出现
+///
+/// `words_only` 时只输出完整的拉丁字母等单词，不输出前缀和中日韩文字，用于模糊匹配
+#[derive(Clone, Default)]
+pub struct FilenameTokenizer {
+    tokens: Vec<Token>,
+    words_only: bool,
+}
+
+impl FilenameTokenizer {
+    pub fn words_only() -> Self {
+        Self {
+            tokens: Vec::new(),
+            words_only: true,
+        }
+    }
+}
+
+/// 在字母数字序列中查找单词边界：小写转大写（`fileName`）、连续大写后接小写（`XMLParser` 的 `P` 前）、字母与数字之间
+fn word_boundaries(chars: &[(usize, char)]) -> Vec<usize> {
+    let mut bounds = vec![0];
+    for i in 1..chars.len() {
+        let (prev, cur) = (chars[i - 1].1, chars[i].1);
+        let next = chars.get(i + 1).map(|c| c.1);
+        let camel = prev.is_lowercase() && cur.is_uppercase();
+        let acronym_end = prev.is_uppercase()
+            && cur.is_uppercase()
+            && next.map(|n| n.is_lowercase()).unwrap_or(false);
+        let digit_change = prev.is_numeric() != cur.is_numeric();
+        if camel || acronym_end || digit_change {
+            bounds.push(i);
+        }
+    }
+    bounds.push(chars.len());
+    bounds
+}
+
+impl Tokenizer for FilenameTokenizer {
+    type TokenStream<'a> = VecTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> VecTokenStream<'a> {
+        self.tokens.clear();
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let byte = |k: usize| chars.get(k).map(|c| c.0).unwrap_or(text.len());
+        let push = |tokens: &mut Vec<Token>, from: usize, to: usize| {
+            tokens.push(Token {
+                offset_from: from,
+                offset_to: to,
+                position: 0,
+                text: text[from..to].to_string(),
+                position_length: 1,
+            });
+        };
+        let mut i = 0usize;
+        while i < chars.len() {
+            let cjk = is_cjk(chars[i].1);
+            if !cjk && !chars[i].1.is_alphanumeric() {
+                i += 1;
+                continue;
+            }
+            let mut j = i + 1;
+            while j < chars.len() {
+                let c = chars[j].1;
+                if cjk != is_cjk(c) || !(cjk || c.is_alphanumeric()) {
+                    break;
+                }
+                j += 1;
+            }
+            if cjk {
+                // 只输出单词时跳过中日韩文字
+                let grams = if self.words_only { 0 } else { 3 };
+                for s in i..j {
+                    for n in 1..=grams.min(j - s) {
+                        push(&mut self.tokens, byte(s), byte(s + n));
+                    }
+                }
+            } else {
+                let bounds = word_boundaries(&chars[i..j]);
+                for w in bounds.windows(2) {
+                    let (ws, we) = (i + w[0], i + w[1]);
+                    if !self.words_only {
+                        for end in ws + 1..we.min(ws + MAX_PREFIX_CHARS) {
+                            push(&mut self.tokens, byte(ws), byte(end));
+                        }
+                    }
+                    push(&mut self.tokens, byte(ws), byte(we));
+                }
+            }
+            i = j;
+        }
+        VecTokenStream::new(&mut self.tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::tokenizer::TokenStream;
+
+    fn tokens(text: &str) -> Vec<String> {
+        tokens_with(FilenameTokenizer::default(), text)
+    }
+
+    fn tokens_with(mut tokenizer: FilenameTokenizer, text: &str) -> Vec<String> {
+        let mut stream = tokenizer.token_stream(text);
+        let mut out = Vec::new();
+        while let Some(t) = stream.next() {
+            out.push(t.text.clone());
+        }
+        out
+    }
+
+    #[test]
+    fn test_filename_tokenizer() {
+        let toks = tokens("QuarterlyReport_2024-Q3.xlsx");
+        for w in ["Quarterly", "Report", "2024", "Q", "3", "xlsx", "Quart", "Rep"] {
+            assert!(toks.contains(&w.to_string()), "{}", w);
+        }
+        // 不产生跨分隔符或跨单词的片段
+        assert!(!toks.iter().any(|t| t.contains('_') || t == "yR" || t == "4-Q"));
+        assert!(!toks.contains(&"port".to_string()));
+        let toks = tokens("XMLParser季度报告v2");
+        for w in ["XML", "Parser", "季", "季度", "季度报", "报告", "v", "2"] {
+            assert!(toks.contains(&w.to_string()), "{}", w);
+        }
+        assert!(!toks.contains(&"XMLP".to_string()));
+    }
+
+    #[test]
+    fn test_words_only() {
+        let toks = tokens_with(FilenameTokenizer::words_only(), "QuarterlyReport 季度报告 v2");
+        assert_eq!(toks, vec!["Quarterly", "Report", "v", "2"]);
+    }
+}
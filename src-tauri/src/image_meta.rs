@@ -0,0 +1,293 @@
+use chrono::TimeZone;
+use exif::{In, Tag, Value};
+use quick_xml::events::Event;
+use quick_xml::Reader as XmlReader;
+use std::io::{BufReader, Read};
+use std::{fs, path::PathBuf};
+
+/// 图片元数据（EXIF、XMP 与图片尺寸）
+#[derive(Default, Debug)]
+pub struct ImageMeta {
+    /// 拍摄时间（毫秒时间戳，按本地时区解释）
+    pub taken_at: Option<i64>,
+    /// 相机品牌与型号
+    pub camera: Option<String>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub keywords: Vec<String>,
+    pub description: Option<String>,
+    /// GPS 坐标（纬度、经度）
+    pub gps: Option<(f64, f64)>,
+}
+
+/// 支持读取元数据的图片扩展名
+pub fn is_image_ext(ext: &str) -> bool {
+    matches!(
+        ext,
+        "jpg" | "jpeg" | "png" | "tif" | "tiff" | "heic" | "heif" | "avif" | "webp"
+    )
+}
+
+/// 读取图片的 EXIF、XMP 元数据及尺寸，读取失败的部分留空
+pub fn read_image_meta(path: &PathBuf) -> ImageMeta {
+    let mut meta = ImageMeta::default();
+    if let Ok(size) = imagesize::size(path) {
+        meta.width = Some(size.width as u64);
+        meta.height = Some(size.height as u64);
+    }
+    if let Ok(file) = fs::File::open(path) {
+        let mut reader = BufReader::new(file);
+        if let Ok(exif) = exif::Reader::new().read_from_container(&mut reader) {
+            apply_exif(&mut meta, &exif);
+        }
+    }
+    // XMP 数据包通常位于文件开头，只读取前 1MB
+    let mut bytes = Vec::new();
+    if let Ok(file) = fs::File::open(path) {
+        if file.take(1024 * 1024).read_to_end(&mut bytes).is_ok() {
+            apply_xmp(&mut meta, &bytes);
+        }
+    }
+    meta
+}
+
+fn apply_exif(meta: &mut ImageMeta, exif: &exif::Exif) {
+    let ascii = |tag: Tag| -> Option<String> {
+        match &exif.get_field(tag, In::PRIMARY)?.value {
+            Value::Ascii(v) => v
+                .first()
+                .map(|s| String::from_utf8_lossy(s).trim().to_string())
+                .filter(|s| !s.is_empty()),
+            _ => None,
+        }
+    };
+    let taken = ascii(Tag::DateTimeOriginal).or_else(|| ascii(Tag::DateTime));
+    meta.taken_at = taken.and_then(|s| exif::DateTime::from_ascii(s.as_bytes()).ok()).and_then(|dt| {
+        let naive = chrono::NaiveDate::from_ymd_opt(dt.year as i32, dt.month as u32, dt.day as u32)?
+            .and_hms_opt(dt.hour as u32, dt.minute as u32, dt.second as u32)?;
+        chrono::Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|t| t.timestamp_millis())
+    });
+    // 型号中通常已包含品牌名，避免重复
+    meta.camera = match (ascii(Tag::Make), ascii(Tag::Model)) {
+        (Some(make), Some(model)) if model.to_lowercase().starts_with(&make.to_lowercase()) => {
+            Some(model)
+        }
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model),
+    };
+    if meta.description.is_none() {
+        meta.description = ascii(Tag::ImageDescription);
+    }
+    if meta.width.is_none() {
+        let dim = |tag: Tag| {
+            exif.get_field(tag, In::PRIMARY)
+                .and_then(|f| f.value.get_uint(0))
+                .map(|v| v as u64)
+        };
+        meta.width = dim(Tag::PixelXDimension);
+        meta.height = dim(Tag::PixelYDimension);
+    }
+    let coord = |tag: Tag, ref_tag: Tag, negative: &str| -> Option<f64> {
+        let Value::Rational(v) = &exif.get_field(tag, In::PRIMARY)?.value else {
+            return None;
+        };
+        if v.len() < 3 {
+            return None;
+        }
+        let deg = v[0].to_f64() + v[1].to_f64() / 60.0 + v[2].to_f64() / 3600.0;
+        let sign = if ascii(ref_tag).is_some_and(|r| r.eq_ignore_ascii_case(negative)) {
+            -1.0
+        } else {
+            1.0
+        };
+        Some(sign * deg).filter(|d| d.is_finite())
+    };
+    if let (Some(lat), Some(lon)) = (
+        coord(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S"),
+        coord(Tag::GPSLongitude, Tag::GPSLongitudeRef, "W"),
+    ) {
+        meta.gps = Some((lat, lon));
+    }
+}
+
+/// 从文件中嵌入的 XMP 数据包读取关键字（dc:subject）和描述（dc:description）
+fn apply_xmp(meta: &mut ImageMeta, bytes: &[u8]) {
+    let Some(start) = find_bytes(bytes, b"<x:xmpmeta") else {
+        return;
+    };
+    let Some(len) = find_bytes(&bytes[start..], b"</x:xmpmeta>") else {
+        return;
+    };
+    let xml = String::from_utf8_lossy(&bytes[start..start + len + b"</x:xmpmeta>".len()]);
+    let mut r = XmlReader::from_str(&xml);
+    r.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    // 当前所在的 dc 元素：subject 或 description
+    let mut current: Option<Vec<u8>> = None;
+    let mut in_li = false;
+    loop {
+        match r.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"dc:subject" | b"dc:description" => current = Some(e.name().as_ref().to_vec()),
+                b"rdf:li" => in_li = true,
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"dc:subject" | b"dc:description" => current = None,
+                b"rdf:li" => in_li = false,
+                _ => {}
+            },
+            Ok(Event::Text(t)) if in_li => {
+                let text = r
+                    .decoder()
+                    .decode(t.as_ref())
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+                match current.as_deref() {
+                    Some(b"dc:subject") if !text.is_empty() => meta.keywords.push(text),
+                    Some(b"dc:description") if !text.is_empty() => meta.description = Some(text),
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_xmp() {
+        let xmp = r#"garbage<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="r" xmlns:dc="d">
+<rdf:Description><dc:subject><rdf:Bag><rdf:li>旅行</rdf:li><rdf:li>beach</rdf:li></rdf:Bag></dc:subject>
+<dc:description><rdf:Alt><rdf:li xml:lang="x-default">海边日落</rdf:li></rdf:Alt></dc:description>
+</rdf:Description></rdf:RDF></x:xmpmeta>trailing"#;
+        let mut meta = ImageMeta::default();
+        apply_xmp(&mut meta, xmp.as_bytes());
+        assert_eq!(meta.keywords, vec!["旅行", "beach"]);
+        assert_eq!(meta.description.as_deref(), Some("海边日落"));
+    }
+
+    /// 小端 TIFF 的一个 IFD，`offset` 为其在 TIFF 数据中的位置；超过 4 字节的值写在 IFD 之后
+    fn ifd(offset: u32, entries: &[(u16, u16, u32, Vec<u8>)]) -> Vec<u8> {
+        let mut out = (entries.len() as u16).to_le_bytes().to_vec();
+        let mut data: Vec<u8> = Vec::new();
+        let data_start = offset + 2 + 12 * entries.len() as u32 + 4;
+        for (tag, typ, count, value) in entries {
+            out.extend(tag.to_le_bytes());
+            out.extend(typ.to_le_bytes());
+            out.extend(count.to_le_bytes());
+            if value.len() <= 4 {
+                let mut v = value.clone();
+                v.resize(4, 0);
+                out.extend(v);
+            } else {
+                out.extend((data_start + data.len() as u32).to_le_bytes());
+                data.extend(value);
+            }
+        }
+        out.extend(0u32.to_le_bytes());
+        out.extend(data);
+        out
+    }
+
+    /// 只含 EXIF 的最小 JPEG：品牌、型号、拍摄时间、像素尺寸和 GPS 坐标
+    fn exif_jpeg() -> Vec<u8> {
+        let ascii = |s: &str| (2u16, s.len() as u32 + 1, [s.as_bytes(), &[0]].concat());
+        let long = |v: u32| (4u16, 1u32, v.to_le_bytes().to_vec());
+        let rational = |v: [u32; 3]| {
+            let bytes = v.iter().flat_map(|n| [n.to_le_bytes(), 1u32.to_le_bytes()].concat());
+            (5u16, 3u32, bytes.collect::<Vec<u8>>())
+        };
+        let entry = |tag: u16, (typ, count, value): (u16, u32, Vec<u8>)| (tag, typ, count, value);
+        let ifd0 = |exif_at: u32, gps_at: u32| {
+            ifd(
+                8,
+                &[
+                    entry(0x010F, ascii("Canon")),
+                    entry(0x0110, ascii("Canon EOS R5")),
+                    entry(0x8769, long(exif_at)),
+                    entry(0x8825, long(gps_at)),
+                ],
+            )
+        };
+        let exif_at = 8 + ifd0(0, 0).len() as u32;
+        let exif_ifd = ifd(
+            exif_at,
+            &[
+                entry(0x9003, ascii("2023:07:15 18:30:00")),
+                entry(0xA002, long(640)),
+                entry(0xA003, long(480)),
+            ],
+        );
+        let gps_at = exif_at + exif_ifd.len() as u32;
+        let gps_ifd = ifd(
+            gps_at,
+            &[
+                entry(0x0001, ascii("S")),
+                entry(0x0002, rational([31, 15, 0])),
+                entry(0x0003, ascii("E")),
+                entry(0x0004, rational([121, 30, 0])),
+            ],
+        );
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend(8u32.to_le_bytes());
+        tiff.extend(ifd0(exif_at, gps_at));
+        tiff.extend(exif_ifd);
+        tiff.extend(gps_ifd);
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend((2 + 6 + tiff.len() as u16).to_be_bytes());
+        jpeg.extend(b"Exif\0\0");
+        jpeg.extend(tiff);
+        jpeg.extend([0xFF, 0xD9]);
+        jpeg
+    }
+
+    #[test]
+    fn test_read_image_meta_exif() {
+        let tmp = std::env::temp_dir().join("test_image_meta_exif.jpg");
+        fs::write(&tmp, exif_jpeg()).unwrap();
+        let meta = read_image_meta(&tmp);
+        // 型号已包含品牌名，不重复
+        assert_eq!(meta.camera.as_deref(), Some("Canon EOS R5"));
+        let taken = chrono::NaiveDate::from_ymd_opt(2023, 7, 15)
+            .and_then(|d| d.and_hms_opt(18, 30, 0))
+            .and_then(|t| chrono::Local.from_local_datetime(&t).earliest())
+            .map(|t| t.timestamp_millis());
+        assert_eq!(meta.taken_at, taken);
+        // 没有 SOF 段，尺寸取自 EXIF
+        assert_eq!((meta.width, meta.height), (Some(640), Some(480)));
+        assert_eq!(meta.gps, Some((-31.25, 121.5)));
+        let _ = fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_read_image_meta_png_size() {
+        // 1x1 PNG，只有尺寸信息
+        let png: [u8; 67] = [
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00,
+            0x00, 0x1F, 0x15, 0xC4, 0x89, 0x00, 0x00, 0x00, 0x0A, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0x9C, 0x63, 0x00, 0x01, 0x00, 0x00, 0x05, 0x00, 0x01, 0x0D, 0x0A, 0x2D, 0xB4, 0x00,
+            0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+        ];
+        let tmp = std::env::temp_dir().join("test_image_meta.png");
+        fs::write(&tmp, png).unwrap();
+        let meta = read_image_meta(&tmp);
+        assert_eq!((meta.width, meta.height), (Some(1), Some(1)));
+        assert!(meta.taken_at.is_none());
+        let _ = fs::remove_file(&tmp);
+    }
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 索引的重建状态，同一索引同一时间只允许一次重建
+#[derive(Default)]
+pub struct RebuildState {
+    building: Arc<AtomicBool>,
+}
+
+impl RebuildState {
+    /// 开始重建，已有重建在进行时返回 `None`
+    ///
+    /// 返回的 guard 释放时清除重建状态，重建线程 panic 时同样会释放
+    pub fn try_start(&self) -> Option<RebuildGuard> {
+        if self.building.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        Some(RebuildGuard(self.building.clone()))
+    }
+}
+
+/// 进行中的重建，释放时清除重建状态
+pub struct RebuildGuard(Arc<AtomicBool>);
+
+impl Drop for RebuildGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 与索引目录并列的同名路径，加上后缀 `suffix`
+fn sibling_path(base: &Path, suffix: &str) -> PathBuf {
+    let mut name = base.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    base.with_file_name(name)
+}
+
+/// 记录当前索引版本目录名的指针文件 `<base>.current`
+fn pointer_file(base: &Path) -> PathBuf {
+    sibling_path(base, ".current")
+}
+
+/// 是否是 `base` 的某个索引版本目录 `<base>-<毫秒时间戳>`
+fn is_version_of(base: &Path, name: &str) -> bool {
+    let base_name = base.file_name().unwrap_or_default().to_string_lossy();
+    name.strip_prefix(base_name.as_ref())
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|ts| !ts.is_empty() && ts.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// 当前使用的索引目录：指针文件指向的版本目录，没有指针文件时为旧版本直接建在 `base` 中的索引
+pub fn live_index_dir(base: &Path) -> PathBuf {
+    fs::read_to_string(pointer_file(base))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| is_version_of(base, name))
+        .map(|name| base.with_file_name(name))
+        .filter(|dir| dir.exists())
+        .unwrap_or_else(|| base.to_path_buf())
+}
+
+/// 在新的版本目录中调用 `build` 建立索引，成功后改写指针文件切换到新版本，重建期间仍可搜索原索引
+///
+/// 切换时不移动或覆盖正在使用的目录（Windows 上无法重命名仍被打开的目录），只改写指针文件；
+/// 之后尽量删除其他版本，仍被打开而删除失败的版本留到下次重建时再删除
+pub fn build_index_version<T>(
+    base: &Path,
+    build: impl FnOnce(&Path) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut ts = chrono::Utc::now().timestamp_millis();
+    let mut version_dir = sibling_path(base, &format!("-{}", ts));
+    while version_dir.exists() {
+        ts += 1;
+        version_dir = sibling_path(base, &format!("-{}", ts));
+    }
+    let result = build(&version_dir).and_then(|value| {
+        let pointer = pointer_file(base);
+        let tmp = sibling_path(base, ".current.tmp");
+        let name = version_dir.file_name().unwrap_or_default().to_string_lossy();
+        fs::write(&tmp, name.as_bytes()).map_err(|e| format!("write index pointer error: {}", e))?;
+        fs::rename(&tmp, &pointer).map_err(|e| format!("write index pointer error: {}", e))?;
+        Ok(value)
+    });
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&version_dir);
+        return result;
+    }
+    if let Some(entries) = base.parent().and_then(|p| fs::read_dir(p).ok()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path != version_dir && is_version_of(base, &name) {
+                let _ = fs::remove_dir_all(&path);
+            }
+        }
+    }
+    let _ = fs::remove_dir_all(base);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebuild_state() {
+        let state = RebuildState::default();
+        let guard = state.try_start().unwrap();
+        assert!(state.try_start().is_none());
+        drop(guard);
+        // 重建线程 panic 时 guard 同样被释放
+        let guard = state.try_start().unwrap();
+        let _ = std::thread::spawn(move || {
+            let _guard = guard;
+            panic!("rebuild failed");
+        })
+        .join();
+        assert!(state.try_start().is_some());
+    }
+
+    #[test]
+    fn test_build_index_version() {
+        let root = std::env::temp_dir().join("test_build_index_version");
+        let _ = fs::remove_dir_all(&root);
+        let base = root.join("default");
+        // 旧版本直接建在 `base` 中的索引在切换到新版本前仍可使用
+        fs::create_dir_all(&base).unwrap();
+        assert_eq!(live_index_dir(&base), base);
+        let write = |dir: &Path, text: &str| {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            fs::write(dir.join("data"), text).map_err(|e| e.to_string())
+        };
+        build_index_version(&base, |dir| write(dir, "v1")).unwrap();
+        let v1 = live_index_dir(&base);
+        assert_ne!(v1, base);
+        assert!(!base.exists());
+        build_index_version(&base, |dir| write(dir, "v2")).unwrap();
+        let v2 = live_index_dir(&base);
+        assert_eq!(fs::read_to_string(v2.join("data")).unwrap(), "v2");
+        assert!(!v1.exists());
+        // 建立失败时保留当前版本，并删除未完成的版本目录
+        let err = build_index_version(&base, |dir| {
+            write(dir, "v3")?;
+            Err::<(), _>("failed".to_string())
+        });
+        assert!(err.is_err());
+        assert_eq!(live_index_dir(&base), v2);
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
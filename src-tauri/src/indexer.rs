@@ -1,21 +1,53 @@
-use calamine::{open_workbook_auto, DataType, Reader as CalReader};
+use calamine::{
+    open_workbook, open_workbook_auto, DataType, Reader as CalReader, Sheets, Xls, Xlsx,
+};
 use pdf_extract::extract_text;
 use quick_xml::events::Event;
 use quick_xml::Reader as XmlReader;
 use quick_xml::escape::unescape;
-use std::{fs, path::PathBuf, thread};
-use tantivy::schema::{Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+};
+use tantivy::schema::{
+    Field, FieldType, IndexRecordOption, JsonObjectOptions, OwnedValue, Schema, TextFieldIndexing,
+    TextOptions, Value,
+};
 use tantivy::schema::{FAST, INDEXED, STORED};
-use tantivy::tokenizer::{LowerCaser, NgramTokenizer, TextAnalyzer};
+use tantivy::tokenizer::{
+    LowerCaser, NgramTokenizer, RawTokenizer, RemoveLongFilter, TextAnalyzer, WhitespaceTokenizer,
+};
 use tantivy::TantivyDocument;
 use tauri::AppHandle;
 use tauri::Emitter;
 use tauri::Manager;
 use zip::ZipArchive;
 
+use crate::csv_reader::read_csv;
+use crate::file_detect::detect_file_type;
+use crate::filename_tokenizer::FilenameTokenizer;
+use crate::image_meta::{is_image_ext, read_image_meta};
+use crate::jieba_tokenizer::{load_jieba, JiebaTokenizer};
+use crate::markdown::{parse_markdown, HeadingScanner};
+use crate::media_meta::{is_audio_ext, is_video_ext, read_media_meta};
+use crate::index_store::{build_index_version, RebuildState};
+use crate::mixed_tokenizer::{text_analyzer, text_language, MixedTokenizer, TEXT_LANGUAGES};
+use crate::pinyin_tokenizer::PinyinTokenizer;
+use crate::settings::{load_settings, ChineseTokenizer, IndexingSettings};
+use crate::symbols::{extract_symbols, is_code_ext};
 use crate::types::{DirectoryConfigCmd, IndexProgressPayload};
+use crate::unicode_norm::{ancestor_dir_keys, nfc, path_key};
+use crate::zh_normalize::ZhNormalizer;
 
-/// 获取应用默认索引目录
+/// 内容索引的重建状态，由应用统一管理，同一时间只允许一次重建
+#[derive(Default)]
+pub struct IndexState {
+    rebuild: RebuildState,
+}
+
+/// 获取应用默认索引目录，实际的索引建在其版本目录中，见 `live_index_dir`
 pub fn app_index_dir(app: &AppHandle) -> PathBuf {
     let resolver = app.path();
     let base_dir = resolver
@@ -24,28 +56,106 @@ pub fn app_index_dir(app: &AppHandle) -> PathBuf {
     base_dir.join("indexes").join("default")
 }
 
-/// 注册中文 n-gram 分词器（标题使用 2-3 字符，内容使用 1-3 字符，大小写归一）
-pub fn register_tokenizers_for(index: &tantivy::Index) {
+/// 注册中文 n-gram 分词器（`cn_ngram` 为 2-3 字符，`cn_ngram_small` 为 1-3 字符，供旧版索引的标题使用），文件名使用的文件名分词器，符号名使用的整词分词器、正则搜索使用的空白分词器以及拼音分词器
+///
+/// 文本分词器的词项统一做繁简、全半角和大小写归一，索引和查询时繁体与简体、全角与半角可互相匹配
+///
+/// 每种文本语言注册一个中英混排分词器（`mixed_<语言>`），英文等按单词分词并提取词干；
+/// 旧版索引的标题使用 1-3 字符的 `mixed_small_<语言>`，同样注册以便打开旧版索引
+///
+/// 索引使用词典分词（jieba）时同时注册 `cn_jieba` 及各语言的 `cn_jieba_<语言>`，词典加载较慢，未使用时不注册
+pub fn register_tokenizers_for(index: &tantivy::Index, settings: &IndexingSettings) {
     let analyzer = TextAnalyzer::builder(NgramTokenizer::new(2, 3, false).unwrap())
+        .filter(ZhNormalizer)
         .filter(LowerCaser)
         .build();
     let analyzer_small = TextAnalyzer::builder(NgramTokenizer::new(1, 3, false).unwrap())
+        .filter(ZhNormalizer)
         .filter(LowerCaser)
         .build();
     index.tokenizers().register("cn_ngram", analyzer);
     index
         .tokenizers()
         .register("cn_ngram_small", analyzer_small);
+    let filename_analyzer = TextAnalyzer::builder(FilenameTokenizer::default())
+        .filter(ZhNormalizer)
+        .filter(LowerCaser)
+        .build();
+    index.tokenizers().register("filename", filename_analyzer);
+    // 只切分出完整单词（不含前缀和中文 n-gram），模糊匹配时在这些词项上查找拼写相近的词
+    let words_analyzer = TextAnalyzer::builder(FilenameTokenizer::words_only())
+        .filter(RemoveLongFilter::limit(40))
+        .filter(ZhNormalizer)
+        .filter(LowerCaser)
+        .build();
+    index.tokenizers().register("words", words_analyzer);
+    let symbol_analyzer = TextAnalyzer::builder(RawTokenizer::default())
+        .filter(ZhNormalizer)
+        .filter(LowerCaser)
+        .build();
+    index.tokenizers().register("symbol", symbol_analyzer);
+    // 按空白切分、保留原文（不归一大小写和写法），正则搜索时在词项上匹配以筛选候选文档，过长的词项（如压缩后的代码）不索引
+    let regex_analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+        .filter(RemoveLongFilter::limit(256))
+        .build();
+    index.tokenizers().register("regex_tokens", regex_analyzer);
+    index
+        .tokenizers()
+        .register("pinyin", TextAnalyzer::from(PinyinTokenizer::default()));
+    for (code, language) in TEXT_LANGUAGES {
+        index.tokenizers().register(
+            &format!("mixed_{}", code),
+            text_analyzer(MixedTokenizer::new(2, 3), *language),
+        );
+        index.tokenizers().register(
+            &format!("mixed_small_{}", code),
+            text_analyzer(MixedTokenizer::new(1, 3), *language),
+        );
+    }
+    let schema = index.schema();
+    let uses_jieba = schema
+        .get_field("content")
+        .ok()
+        .and_then(|f| match schema.get_field_entry(f).field_type() {
+            FieldType::Str(opts) => opts
+                .get_indexing_options()
+                .map(|i| i.tokenizer().starts_with("cn_jieba")),
+            _ => None,
+        })
+        .unwrap_or(false);
+    if uses_jieba {
+        let jieba = load_jieba(settings.user_dictionary.as_deref());
+        let jieba_analyzer = TextAnalyzer::builder(JiebaTokenizer::new(jieba.clone()))
+            .filter(ZhNormalizer)
+            .filter(LowerCaser)
+            .build();
+        index.tokenizers().register("cn_jieba", jieba_analyzer);
+        for (code, language) in TEXT_LANGUAGES {
+            index.tokenizers().register(
+                &format!("cn_jieba_{}", code),
+                text_analyzer(JiebaTokenizer::new(jieba.clone()), *language),
+            );
+        }
+    }
 }
 
-/// 构建索引schema（含中文 n-gram 分词支持），包含标题、内容、文件路径、文件类型、修改时间、文件大小字段
-fn build_schema() -> Schema {
+/// 构建索引schema（中文按设置使用 n-gram 或词典分词），包含标题、内容、文件路径及所在目录、文件类型、修改时间、文件大小、Markdown 元数据、源代码符号、CSV 单元格、段落、图片、音视频元数据及拼音字段
+fn build_schema(settings: &IndexingSettings) -> Schema {
     let mut schema_builder = tantivy::schema::SchemaBuilder::default();
+    // 内容等中文文本字段使用的分词器，文件名（title）总是使用文件名分词器
+    // 设置了文本语言时分词器名称带语言后缀，查询时按索引的分词器名称使用对应语言
+    let language = text_language(settings.text_language.as_deref());
+    let text_tokenizer = match (settings.tokenizer, language) {
+        (ChineseTokenizer::Ngram, None) => "cn_ngram".to_string(),
+        (ChineseTokenizer::Ngram, Some(code)) => format!("mixed_{}", code),
+        (ChineseTokenizer::Jieba, None) => "cn_jieba".to_string(),
+        (ChineseTokenizer::Jieba, Some(code)) => format!("cn_jieba_{}", code),
+    };
     let text_indexing_title = TextFieldIndexing::default()
-        .set_tokenizer("cn_ngram_small")
+        .set_tokenizer("filename")
         .set_index_option(IndexRecordOption::WithFreqsAndPositions);
     let text_indexing_content = TextFieldIndexing::default()
-        .set_tokenizer("cn_ngram")
+        .set_tokenizer(&text_tokenizer)
         .set_index_option(IndexRecordOption::WithFreqsAndPositions);
     let title_options = TextOptions::default()
         .set_indexing_options(text_indexing_title)
@@ -54,8 +164,32 @@ fn build_schema() -> Schema {
         .set_indexing_options(text_indexing_content)
         .set_stored();
     schema_builder.add_text_field("title", title_options);
-    schema_builder.add_text_field("content", content_options);
-    schema_builder.add_text_field("file_path", STORED);
+    schema_builder.add_text_field("content", content_options.clone());
+    // meta_title：Markdown front matter、音视频标签中的标题，与内容使用相同的分词器
+    schema_builder.add_text_field("meta_title", content_options);
+    // file_path：存储并作为快速字段，搜索时按文件路径将段落归组
+    schema_builder.add_text_field(
+        "file_path",
+        TextOptions::default().set_stored().set_fast(None),
+    );
+    // path：文件所在目录的各级名称，按文件名分词器切分，可搜索文件夹名称
+    let path_indexing = TextFieldIndexing::default()
+        .set_tokenizer("filename")
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+    schema_builder.add_text_field(
+        "path",
+        TextOptions::default().set_indexing_options(path_indexing),
+    );
+    // root_dir：文件所属的索引目录（目录配置中的路径），快速字段，搜索时按索引目录统计命中数
+    schema_builder.add_text_field("root_dir", TextOptions::default().set_fast(None));
+    // dirs：文件所在的各级目录（规范化后的完整路径），精确匹配，用于按目录筛选
+    let dirs_indexing = TextFieldIndexing::default()
+        .set_tokenizer("raw")
+        .set_index_option(IndexRecordOption::Basic);
+    schema_builder.add_text_field(
+        "dirs",
+        TextOptions::default().set_indexing_options(dirs_indexing),
+    );
     // file_type: 作为精确匹配，使用 raw 分词器并存储
     let ft_indexing = TextFieldIndexing::default()
         .set_tokenizer("raw")
@@ -63,49 +197,238 @@ fn build_schema() -> Schema {
     let ft_options = TextOptions::default()
         .set_indexing_options(ft_indexing)
         .set_stored();
-    schema_builder.add_text_field("file_type", ft_options);
+    // file_type 同时作为快速字段，搜索时按文件类型统计命中数
+    schema_builder.add_text_field("file_type", ft_options.clone().set_fast(None));
+    // mime_type：按文件头部签名识别出的 MIME 类型，精确匹配
+    schema_builder.add_text_field("mime_type", ft_options);
     // modified_time：数值字段，默认可用于 RangeQuery，同时存储
     schema_builder.add_i64_field("modified_time", INDEXED | FAST | STORED);
-    schema_builder.add_u64_field("file_size", STORED);
+    // file_size：文件大小（字节），可用于按大小区间筛选
+    schema_builder.add_u64_field("file_size", INDEXED | FAST | STORED);
+    // headings：Markdown 标题，单独建字段以便查询时加权
+    let headings_indexing = TextFieldIndexing::default()
+        .set_tokenizer(&text_tokenizer)
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+    schema_builder.add_text_field(
+        "headings",
+        TextOptions::default().set_indexing_options(headings_indexing),
+    );
+    // tags：front matter 中的标签，精确匹配
+    let tags_indexing = TextFieldIndexing::default()
+        .set_tokenizer("raw")
+        .set_index_option(IndexRecordOption::Basic);
+    schema_builder.add_text_field(
+        "tags",
+        TextOptions::default()
+            .set_indexing_options(tags_indexing)
+            .set_stored(),
+    );
+    // doc_date：front matter 中的日期
+    schema_builder.add_i64_field("doc_date", INDEXED | FAST | STORED);
+    // symbol：源代码中的符号名（整词、忽略大小写），symbol_line 按相同顺序存储定义所在行号
+    let symbol_indexing = TextFieldIndexing::default()
+        .set_tokenizer("symbol")
+        .set_index_option(IndexRecordOption::WithFreqs);
+    schema_builder.add_text_field(
+        "symbol",
+        TextOptions::default()
+            .set_indexing_options(symbol_indexing)
+            .set_stored(),
+    );
+    schema_builder.add_u64_field("symbol_line", STORED);
+    // columns：CSV/TSV 按表头索引的单元格，每行一个 JSON 对象，可用 `columns.表头:值` 查询
+    let columns_indexing = TextFieldIndexing::default()
+        .set_tokenizer(&text_tokenizer)
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+    schema_builder.add_json_field(
+        "columns",
+        JsonObjectOptions::default().set_indexing_options(columns_indexing),
+    );
+    // line_start：按行分块索引的大文本文件中，分块起始行号（从 1 开始）
+    schema_builder.add_u64_field("line_start", STORED);
+    // passage_*：长文档拆分出的段落子文档的类型、序号（从 1 开始）和名称
+    schema_builder.add_text_field("passage_kind", STORED);
+    schema_builder.add_u64_field("passage_no", FAST | STORED);
+    schema_builder.add_text_field("passage_name", STORED);
+    // 图片元数据：拍摄时间、相机（按单词分词，可用于筛选）、尺寸和 GPS 坐标
+    schema_builder.add_i64_field("taken_at", INDEXED | FAST | STORED);
+    let camera_indexing = TextFieldIndexing::default()
+        .set_tokenizer("default")
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+    schema_builder.add_text_field(
+        "camera",
+        TextOptions::default()
+            .set_indexing_options(camera_indexing)
+            .set_stored(),
+    );
+    schema_builder.add_u64_field("width", INDEXED | FAST | STORED);
+    schema_builder.add_u64_field("height", INDEXED | FAST | STORED);
+    schema_builder.add_f64_field("gps_lat", INDEXED | FAST | STORED);
+    schema_builder.add_f64_field("gps_lon", INDEXED | FAST | STORED);
+    // 音视频元数据：艺术家、专辑（中文 n-gram 分词），年份和时长（秒）
+    let media_indexing = TextFieldIndexing::default()
+        .set_tokenizer(&text_tokenizer)
+        .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+    let media_options = TextOptions::default()
+        .set_indexing_options(media_indexing)
+        .set_stored();
+    schema_builder.add_text_field("artist", media_options.clone());
+    schema_builder.add_text_field("album", media_options);
+    schema_builder.add_u64_field("year", INDEXED | FAST | STORED);
+    schema_builder.add_u64_field("duration", INDEXED | FAST | STORED);
+    // title_pinyin、content_pinyin：标题和内容的拼音（全拼与首字母），查询时按前缀匹配
+    let pinyin_indexing = TextFieldIndexing::default()
+        .set_tokenizer("pinyin")
+        .set_index_option(IndexRecordOption::WithFreqs);
+    let pinyin_options = TextOptions::default().set_indexing_options(pinyin_indexing);
+    schema_builder.add_text_field("title_pinyin", pinyin_options.clone());
+    schema_builder.add_text_field("content_pinyin", pinyin_options);
+    // content_tokens：标题和内容按空白切分的原文词项，设置中开启后写入，正则搜索时用于筛选候选文档
+    let tokens_indexing = TextFieldIndexing::default()
+        .set_tokenizer("regex_tokens")
+        .set_index_option(IndexRecordOption::Basic);
+    schema_builder.add_text_field(
+        "content_tokens",
+        TextOptions::default().set_indexing_options(tokens_indexing),
+    );
+    // words：标题（以及设置中开启后的内容）中的完整单词（小写），模糊匹配时使用
+    let words_indexing = TextFieldIndexing::default()
+        .set_tokenizer("words")
+        .set_index_option(IndexRecordOption::Basic);
+    schema_builder.add_text_field(
+        "words",
+        TextOptions::default().set_indexing_options(words_indexing),
+    );
     schema_builder.build()
 }
 
-/// 获取索引schema中定义的字段（标题、内容、文件路径、文件类型、修改时间、文件大小）
-pub fn index_fields(schema: &Schema) -> (Field, Field, Field, Field, Field, Field) {
-    let title = schema.get_field("title").unwrap();
-    let content = schema.get_field("content").unwrap();
-    let file_path = schema.get_field("file_path").unwrap();
-    let file_type = schema.get_field("file_type").unwrap();
-    let modified_time = schema.get_field("modified_time").unwrap();
-    let file_size = schema.get_field("file_size").unwrap();
-    (title, content, file_path, file_type, modified_time, file_size)
+/// 索引schema中定义的字段
+#[derive(Clone, Copy)]
+pub struct IndexFields {
+    pub title: Field,
+    pub meta_title: Field,
+    pub content: Field,
+    pub file_path: Field,
+    pub path: Field,
+    pub dirs: Field,
+    pub root_dir: Field,
+    pub file_type: Field,
+    pub mime_type: Field,
+    pub modified_time: Field,
+    pub file_size: Field,
+    pub headings: Field,
+    pub tags: Field,
+    pub doc_date: Field,
+    pub symbol: Field,
+    pub symbol_line: Field,
+    pub columns: Field,
+    pub line_start: Field,
+    pub passage_kind: Field,
+    pub passage_no: Field,
+    pub passage_name: Field,
+    pub taken_at: Field,
+    pub camera: Field,
+    pub width: Field,
+    pub height: Field,
+    pub gps_lat: Field,
+    pub gps_lon: Field,
+    pub artist: Field,
+    pub album: Field,
+    pub year: Field,
+    pub duration: Field,
+    pub title_pinyin: Field,
+    pub content_pinyin: Field,
+    pub content_tokens: Field,
+    pub words: Field,
 }
 
-/// 从目录配置中递归或非递归收集所有文件路径，是否递归取决于配置
-fn collect_files_from_dirs(directories: Vec<DirectoryConfigCmd>) -> Vec<PathBuf> {
-    let mut files: Vec<PathBuf> = Vec::new();
+/// 获取索引schema中定义的字段，旧版本索引缺少字段时返回错误，需要重建索引
+pub fn index_fields(schema: &Schema) -> Result<IndexFields, String> {
+    let field = |name: &str| {
+        schema
+            .get_field(name)
+            .map_err(|_| format!("index schema outdated, missing field `{}`, please rebuild index", name))
+    };
+    Ok(IndexFields {
+        title: field("title")?,
+        meta_title: field("meta_title")?,
+        content: field("content")?,
+        file_path: field("file_path")?,
+        path: field("path")?,
+        dirs: field("dirs")?,
+        root_dir: field("root_dir")?,
+        file_type: field("file_type")?,
+        mime_type: field("mime_type")?,
+        modified_time: field("modified_time")?,
+        file_size: field("file_size")?,
+        headings: field("headings")?,
+        tags: field("tags")?,
+        doc_date: field("doc_date")?,
+        symbol: field("symbol")?,
+        symbol_line: field("symbol_line")?,
+        columns: field("columns")?,
+        line_start: field("line_start")?,
+        passage_kind: field("passage_kind")?,
+        passage_no: field("passage_no")?,
+        passage_name: field("passage_name")?,
+        taken_at: field("taken_at")?,
+        camera: field("camera")?,
+        width: field("width")?,
+        height: field("height")?,
+        gps_lat: field("gps_lat")?,
+        gps_lon: field("gps_lon")?,
+        artist: field("artist")?,
+        album: field("album")?,
+        year: field("year")?,
+        duration: field("duration")?,
+        title_pinyin: field("title_pinyin")?,
+        content_pinyin: field("content_pinyin")?,
+        content_tokens: field("content_tokens")?,
+        words: field("words")?,
+    })
+}
+
+/// 遍历目录配置中的所有文件，依次回调文件路径、元数据及其所属的索引目录，是否递归取决于配置
+///
+/// 目录重叠或同一路径以 NFC、NFD 两种形式出现时，同一文件只访问一次（归属先配置的目录）
+pub fn walk_files(
+    directories: Vec<DirectoryConfigCmd>,
+    mut visit: impl FnMut(PathBuf, fs::Metadata, &str),
+) {
+    let mut seen = HashSet::new();
     for d in directories.into_iter().filter(|d| d.enabled) {
-        let p = PathBuf::from(d.path);
-        if d.recursive {
-            for entry in walkdir::WalkDir::new(&p).into_iter().filter_map(|e| e.ok()) {
-                let meta = entry.metadata().ok();
-                if let Some(m) = meta {
-                    if m.is_file() {
-                        files.push(entry.path().to_path_buf());
-                    }
-                }
-            }
+        let p = PathBuf::from(&d.path);
+        let entries: Box<dyn Iterator<Item = (PathBuf, fs::Metadata)>> = if d.recursive {
+            Box::new(
+                walkdir::WalkDir::new(&p)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter_map(|e| Some((e.path().to_path_buf(), e.metadata().ok()?))),
+            )
         } else {
-            if let Ok(read_dir) = fs::read_dir(&p) {
-                for entry in read_dir.flatten() {
-                    let path = entry.path();
-                    if path.is_file() {
-                        files.push(path);
-                    }
-                }
+            Box::new(
+                fs::read_dir(&p)
+                    .into_iter()
+                    .flat_map(|rd| rd.flatten())
+                    .filter_map(|e| {
+                        let path = e.path();
+                        let meta = fs::metadata(&path).ok()?;
+                        Some((path, meta))
+                    }),
+            )
+        };
+        for (path, meta) in entries {
+            if meta.is_file() && seen.insert(path_key(&path)) {
+                visit(path, meta, &d.path);
             }
         }
     }
+}
+
+/// 从目录配置中收集所有文件路径及其所属的索引目录，见 `walk_files`
+fn collect_files_from_dirs(directories: Vec<DirectoryConfigCmd>) -> Vec<(PathBuf, String)> {
+    let mut files: Vec<(PathBuf, String)> = Vec::new();
+    walk_files(directories, |path, _, root| files.push((path, root.to_string())));
     files
 }
 
@@ -128,78 +451,389 @@ fn emit_index_progress(
             indexed_files: indexed,
             index_size: size,
             last_updated,
+            error: None,
+        },
+    );
+}
+
+/// 向前端发送索引重建失败事件
+fn emit_index_error(app: &AppHandle, total: usize, error: String) {
+    let _ = app.emit(
+        "index-progress",
+        IndexProgressPayload {
+            is_indexing: false,
+            progress: 0,
+            total_files: total,
+            indexed_files: 0,
+            index_size: 0,
+            last_updated: 0,
+            error: Some(error),
         },
     );
 }
 
+/// 长文档中的一个段落（页、幻灯片、工作表或按长度切分的文本块），作为子文档索引
+struct Passage {
+    /// 段落类型：page、slide、sheet、lines、text
+    kind: &'static str,
+    /// 段落名称，如工作表名、段落开始处所在的 Markdown 标题
+    name: Option<String>,
+    text: String,
+    /// 按行分块时分块的起始行号
+    line_start: Option<u64>,
+}
+
 /// 从文件路径构建tantivy文档，包含标题、内容、文件路径、文件类型、修改时间、文件大小字段
-fn make_doc(
+///
+/// 长文档拆分为一个父文档（标题与元数据，不含内容）和多个段落子文档（段落内容及文件信息），
+/// 搜索时按文件路径将父文档与子文档归为同一个结果
+fn make_docs(
     path: &PathBuf,
-    title: Field,
-    content: Field,
-    file_path: Field,
-    file_type: Field,
-    modified_time: Field,
-    file_size: Field,
-) -> TantivyDocument {
-    let ext = path
+    root: &str,
+    fields: &IndexFields,
+    settings: &IndexingSettings,
+) -> Vec<TantivyDocument> {
+    let name_ext = path
         .extension()
         .and_then(|s| s.to_str())
         .unwrap_or("")
         .to_lowercase();
-    let meta = fs::metadata(path).ok();
-    let mut text = String::new();
+    // 按文件头部签名识别实际类型，扩展名错误或缺失时也能选择正确的解析器
+    let detected = detect_file_type(path, &name_ext);
+    let (ext, file_type, mime) = (detected.ext, detected.file_type, detected.mime);
+    let fname = nfc(path.file_name().and_then(|s| s.to_str()).unwrap_or(""));
+    // 文件名作为标题，front matter、音视频标签中的标题另存为 meta_title
+    let mut doc = TantivyDocument::default();
+    doc.add_text(fields.title, fname);
+
+    let (text, mut passages) = if should_chunk_lines(path, &ext, settings) {
+        (String::new(), read_line_chunks(path, settings.line_chunk_size))
+    } else {
+        extract_content(path, &ext, &mut doc, fields)
+    };
+    // 标题和内容统一为 NFC，与查询的规范化方式一致
+    let text = nfc(&text);
+    if passages.is_empty()
+        && settings.passage_size > 0
+        && !matches!(ext.as_str(), "csv" | "tsv")
+        && text.chars().count() > settings.passage_size
+    {
+        passages = split_text_passages(&text, settings.passage_size, ext == "md");
+    }
+    // 标题写入模糊匹配的单词字段，开启正则搜索的原文词项索引、拼音搜索时额外写入对应字段；内容按设置写入这些字段
+    let titles: Vec<String> = doc
+        .get_all(fields.title)
+        .chain(doc.get_all(fields.meta_title))
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .collect();
+    for t in titles {
+        if settings.pinyin {
+            doc.add_text(fields.title_pinyin, &t);
+        }
+        if settings.regex_index {
+            doc.add_text(fields.content_tokens, &t);
+        }
+        doc.add_text(fields.words, t);
+    }
+    let content_pinyin = settings.pinyin && settings.pinyin_content;
 
-    if matches!(ext.as_str(), "txt" | "md" | "rs" | "js" | "ts" | "json") {
+    if passages.is_empty() {
+        if !text.is_empty() {
+            if content_pinyin {
+                doc.add_text(fields.content_pinyin, &text);
+            }
+            if settings.regex_index {
+                doc.add_text(fields.content_tokens, &text);
+            }
+            if settings.fuzzy_content {
+                doc.add_text(fields.words, &text);
+            }
+            doc.add_text(fields.content, text);
+        }
+        add_file_meta(&mut doc, path, root, &file_type, mime, fields);
+        return vec![doc];
+    }
+    add_file_meta(&mut doc, path, root, &file_type, mime, fields);
+    let mut docs = vec![doc];
+    for (i, p) in passages.into_iter().enumerate() {
+        let mut child = TantivyDocument::default();
+        let passage_text = nfc(&p.text);
+        if content_pinyin {
+            child.add_text(fields.content_pinyin, &passage_text);
+        }
+        if settings.regex_index {
+            child.add_text(fields.content_tokens, &passage_text);
+        }
+        if settings.fuzzy_content {
+            child.add_text(fields.words, &passage_text);
+        }
+        child.add_text(fields.content, passage_text);
+        child.add_text(fields.passage_kind, p.kind);
+        child.add_u64(fields.passage_no, i as u64 + 1);
+        if let Some(name) = p.name {
+            child.add_text(fields.passage_name, nfc(&name));
+        }
+        if let Some(start) = p.line_start {
+            child.add_u64(fields.line_start, start);
+        }
+        add_file_meta(&mut child, path, root, &file_type, mime, fields);
+        docs.push(child);
+    }
+    docs
+}
+
+/// 按文件类型提取文本内容，元数据（Markdown front matter、代码符号、CSV 单元格等）统一为 NFC 后直接写入父文档，
+/// 天然分页/分片的格式（工作表、幻灯片）返回段落
+fn extract_content(
+    path: &PathBuf,
+    ext: &str,
+    doc: &mut TantivyDocument,
+    fields: &IndexFields,
+) -> (String, Vec<Passage>) {
+    let mut text = String::new();
+    let mut passages = Vec::new();
+    if ext == "md" {
+        // Markdown 文件解析 front matter 和标题
+        if let Ok(t) = fs::read_to_string(path) {
+            let md = parse_markdown(&t);
+            if let Some(mt) = md.title {
+                doc.add_text(fields.meta_title, nfc(&mt));
+            }
+            for tag in md.tags {
+                doc.add_text(fields.tags, nfc(&tag).to_lowercase());
+            }
+            if let Some(d) = md.date {
+                doc.add_i64(fields.doc_date, d);
+            }
+            for h in md.headings {
+                doc.add_text(fields.headings, nfc(&h));
+            }
+            text = md.body;
+        }
+    } else if is_code_ext(ext) {
+        // 源代码文件，额外提取符号定义
+        if let Ok(t) = fs::read_to_string(path) {
+            for sym in extract_symbols(ext, &t) {
+                doc.add_text(fields.symbol, nfc(&sym.name));
+                doc.add_u64(fields.symbol_line, sym.line);
+            }
+            text = t;
+        }
+    } else if matches!(ext, "csv" | "tsv") {
+        // CSV/TSV 文件按表头索引单元格
+        let csv = read_csv(path);
+        for row in csv.rows {
+            let obj = row
+                .into_iter()
+                .map(|(h, v)| (nfc(&h), OwnedValue::Str(nfc(&v))))
+                .collect();
+            doc.add_object(fields.columns, obj);
+        }
+        text = csv.text;
+    } else if matches!(ext, "txt" | "log" | "json") {
         // 如果是文本类型文件，直接读取内容
         if let Ok(t) = fs::read_to_string(path) {
             text = t;
         }
-    } else if matches!(ext.as_str(), "docx") {
+    } else if ext == "docx" {
         // 如果是文档类型文件，使用quick-xml库读取内容
         text = read_docx(path);
-    } else if matches!(ext.as_str(), "doc") {
+    } else if ext == "doc" {
         // 纯rust对旧版DOC支持不好，暂时不支持
         // text = read_doc(path);
-    } else if matches!(ext.as_str(), "xls" | "xlsx") {
-        // 如果是Excel类型文件，使用calamine库读取内容
-        text = read_excel(path);
-    } else if ext.as_str() == "pdf" {
+    } else if matches!(ext, "xls" | "xlsx") {
+        // 如果是Excel类型文件，使用calamine库读取内容，多个工作表时每个工作表作为一个段落
+        let mut sheets = read_excel(path, ext);
+        if sheets.len() > 1 {
+            passages = sheets
+                .into_iter()
+                .map(|(name, t)| Passage {
+                    kind: "sheet",
+                    name: Some(name),
+                    text: t,
+                    line_start: None,
+                })
+                .collect();
+        } else if let Some((_, t)) = sheets.pop() {
+            text = t;
+        }
+    } else if ext == "pptx" {
+        // PPT 文档每张幻灯片作为一个段落
+        passages = read_pptx(path)
+            .into_iter()
+            .map(|t| Passage {
+                kind: "slide",
+                name: None,
+                text: t,
+                line_start: None,
+            })
+            .collect();
+    } else if is_image_ext(ext) {
+        // 图片读取 EXIF/XMP 元数据，关键字作为标签，描述作为内容
+        let img = read_image_meta(path);
+        if let Some(t) = img.taken_at {
+            doc.add_i64(fields.taken_at, t);
+        }
+        if let Some(c) = img.camera {
+            doc.add_text(fields.camera, nfc(&c));
+        }
+        if let (Some(w), Some(h)) = (img.width, img.height) {
+            doc.add_u64(fields.width, w);
+            doc.add_u64(fields.height, h);
+        }
+        if let Some((lat, lon)) = img.gps {
+            doc.add_f64(fields.gps_lat, lat);
+            doc.add_f64(fields.gps_lon, lon);
+        }
+        for k in img.keywords {
+            doc.add_text(fields.tags, nfc(&k).to_lowercase());
+        }
+        text = img.description.unwrap_or_default();
+    } else if is_audio_ext(ext) || is_video_ext(ext) {
+        // 音视频读取标签，流派作为标签
+        let media = read_media_meta(path);
+        if let Some(t) = media.title {
+            doc.add_text(fields.meta_title, nfc(&t));
+        }
+        if let Some(a) = media.artist {
+            doc.add_text(fields.artist, nfc(&a));
+        }
+        if let Some(a) = media.album {
+            doc.add_text(fields.album, nfc(&a));
+        }
+        if let Some(y) = media.year {
+            doc.add_u64(fields.year, y);
+        }
+        if let Some(d) = media.duration {
+            doc.add_u64(fields.duration, d);
+        }
+        if let Some(g) = media.genre {
+            doc.add_text(fields.tags, nfc(&g).to_lowercase());
+        }
+    } else if ext == "pdf" {
         // 如果是PDF类型文件，使用pdf-extract库读取内容
         // 2025.12.16 暂时移除对PDF的支持，读取PDF文本会导致索引时间过长
         // text = read_pdf(path);
     }
-    let fname = path
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("")
-        .to_string();
-    let mut doc = TantivyDocument::default();
-    doc.add_text(title, fname);
-    if !text.is_empty() {
-        doc.add_text(content, text);
+    (text, passages)
+}
+
+/// 添加文件路径、所在目录、所属索引目录、文件类型、MIME 类型、修改时间、文件大小字段
+fn add_file_meta(
+    doc: &mut TantivyDocument,
+    path: &PathBuf,
+    root: &str,
+    ext: &str,
+    mime: Option<&str>,
+    fields: &IndexFields,
+) {
+    doc.add_text(fields.file_path, path.to_string_lossy());
+    if let Some(parent) = path.parent() {
+        doc.add_text(fields.path, nfc(&parent.to_string_lossy()));
+    }
+    for dir in ancestor_dir_keys(path) {
+        doc.add_text(fields.dirs, dir);
     }
-    doc.add_text(file_path, path.to_string_lossy());
-    doc.add_text(file_type, ext);
-    if let Some(m) = &meta {
+    doc.add_text(fields.root_dir, root);
+    doc.add_text(fields.file_type, ext);
+    if let Some(m) = mime {
+        doc.add_text(fields.mime_type, m);
+    }
+    if let Ok(m) = fs::metadata(path) {
         let mt = m
             .modified()
             .ok()
             .and_then(|t| t.elapsed().ok())
             .map(|e| chrono::Utc::now().timestamp_millis() - e.as_millis() as i64)
             .unwrap_or(0);
-        doc.add_i64(modified_time, mt);
-        doc.add_u64(file_size, m.len());
+        doc.add_i64(fields.modified_time, mt);
+        doc.add_u64(fields.file_size, m.len());
     }
-    doc
 }
 
-/// 读取Excel文件内容
-fn read_excel(path: &PathBuf) -> String {  
-    if let Ok(mut wb) = open_workbook_auto(path.to_string_lossy().to_string()) {
-        let mut out = String::new();
+/// 是否将文件按行分块索引：开启该设置且为超过阈值大小的日志/文本文件
+fn should_chunk_lines(path: &PathBuf, ext: &str, settings: &IndexingSettings) -> bool {
+    settings.line_chunking
+        && matches!(ext, "log" | "txt")
+        && fs::metadata(path)
+            .map(|m| m.len() >= settings.line_chunk_min_file_size)
+            .unwrap_or(false)
+}
+
+/// 将大文本文件按行分块，每个分块记录起始行号
+fn read_line_chunks(path: &PathBuf, lines_per_chunk: usize) -> Vec<Passage> {
+    // 日志中可能混有非 UTF-8 字节，按有损方式解码
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    let lines: Vec<&str> = text.lines().collect();
+    let size = lines_per_chunk.max(1);
+    lines
+        .chunks(size)
+        .enumerate()
+        .map(|(i, chunk)| Passage {
+            kind: "lines",
+            name: None,
+            text: chunk.join("\n"),
+            line_start: Some((i * size) as u64 + 1),
+        })
+        .collect()
+}
+
+/// 按行边界将长文本切分为约 `size` 个字符的段落；Markdown 记录段落开始处所在的标题
+fn split_text_passages(text: &str, size: usize, markdown: bool) -> Vec<Passage> {
+    let mut passages = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0usize;
+    let mut heading: Option<String> = None;
+    let mut passage_heading: Option<String> = None;
+    let mut scanner = HeadingScanner::default();
+    for line in text.split_inclusive('\n') {
+        if markdown {
+            if let Some(h) = scanner.heading(line) {
+                heading = Some(h.to_string());
+            }
+        }
+        if current.is_empty() {
+            passage_heading = heading.clone();
+        }
+        current.push_str(line);
+        current_chars += line.chars().count();
+        if current_chars >= size {
+            passages.push(Passage {
+                kind: "text",
+                name: passage_heading.take(),
+                text: std::mem::take(&mut current),
+                line_start: None,
+            });
+            current_chars = 0;
+        }
+    }
+    if !current.trim().is_empty() {
+        passages.push(Passage {
+            kind: "text",
+            name: passage_heading,
+            text: current,
+            line_start: None,
+        });
+    }
+    passages
+}
+
+/// 读取Excel文件内容，返回每个工作表的名称和内容
+fn read_excel(path: &PathBuf, ext: &str) -> Vec<(String, String)> {
+    let mut sheets = Vec::new();
+    // 按识别出的实际格式打开，文件扩展名与内容不符时也能读取
+    let workbook = match ext {
+        "xlsx" => open_workbook::<Xlsx<_>, _>(path).ok().map(Sheets::Xlsx),
+        "xls" => open_workbook::<Xls<_>, _>(path).ok().map(Sheets::Xls),
+        _ => open_workbook_auto(path.to_string_lossy().to_string()).ok(),
+    };
+    if let Some(mut wb) = workbook {
         for sheet_name in wb.sheet_names().to_vec() {
             if let Some(Ok(range)) = wb.worksheet_range(&sheet_name) {
+                let mut out = String::new();
                 for row in range.rows() {
                     for c in row {
                         match c {
@@ -224,11 +858,11 @@ fn read_excel(path: &PathBuf) -> String {
                     }
                     out.push('\n');
                 }
+                sheets.push((sheet_name, out));
             }
         }
-        return out;
     }
-    String::new()
+    sheets
 }
 
 /// 读取WORD文档内容
@@ -239,38 +873,71 @@ fn read_docx(path: &PathBuf) -> String {
                 use std::io::Read;
                 let mut xml = String::new();
                 let _ = file.read_to_string(&mut xml);
-                let mut r = XmlReader::from_str(&xml);
-                r.config_mut().trim_text(true);
-                let mut buf = Vec::new();
-                let mut out = String::new();
-                loop {
-                    match r.read_event_into(&mut buf) {
-                        Ok(Event::Start(e)) => if e.name().as_ref() == b"w:t" {},
-                        Ok(Event::Text(t)) => {
-                             // 1. 先用 reader 解码字节 → &str
-                            let decoded = r
-                                .decoder()
-                                .decode(t.as_ref())
-                                .unwrap_or_default();
-
-                            // 2. 再做 XML 实体反转义
-                            let text = unescape(&decoded)
-                                .unwrap_or_default();
-                            out.push_str(&text);
-                            out.push(' ');
-                        }
-                        Ok(Event::Eof) => break,
-                        _ => {}
-                    }
-                    buf.clear();
-                }
-                return out;
+                return xml_text(&xml);
             }
         }
     }
     String::new()
 }
 
+/// 读取PPT文档内容，按幻灯片顺序返回每张幻灯片的文本
+fn read_pptx(path: &PathBuf) -> Vec<String> {
+    let mut slides: Vec<(u32, String)> = Vec::new();
+    if let Ok(f) = fs::File::open(path) {
+        if let Ok(mut zip) = ZipArchive::new(f) {
+            let names: Vec<String> = zip.file_names().map(|n| n.to_string()).collect();
+            for name in names {
+                // 幻灯片位于 ppt/slides/slideN.xml
+                let Some(no) = name
+                    .strip_prefix("ppt/slides/slide")
+                    .and_then(|n| n.strip_suffix(".xml"))
+                    .and_then(|n| n.parse::<u32>().ok())
+                else {
+                    continue;
+                };
+                if let Ok(mut file) = zip.by_name(&name) {
+                    use std::io::Read;
+                    let mut xml = String::new();
+                    let _ = file.read_to_string(&mut xml);
+                    slides.push((no, xml_text(&xml)));
+                }
+            }
+        }
+    }
+    slides.sort_by_key(|(no, _)| *no);
+    slides.into_iter().map(|(_, t)| t).collect()
+}
+
+/// 提取 Office XML 中的所有文本节点，以空格分隔
+fn xml_text(xml: &str) -> String {
+    let mut r = XmlReader::from_str(xml);
+    r.config_mut().trim_text(true);
+    let mut buf = Vec::new();
+    let mut out = String::new();
+    loop {
+        match r.read_event_into(&mut buf) {
+            Ok(Event::Text(t)) => {
+                // 1. 先用 reader 解码字节 → &str
+                let decoded = r
+                    .decoder()
+                    .decode(t.as_ref())
+                    .unwrap_or_default();
+
+                // 2. 再做 XML 实体反转义
+                let text = unescape(&decoded)
+                    .unwrap_or_default();
+                out.push_str(&text);
+                out.push(' ');
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    out
+}
+
 /// 读取旧版WORD文档内容
 #[allow(dead_code)]
 fn read_doc(path: &PathBuf) -> String {
@@ -392,63 +1059,86 @@ fn read_pdf(path: &PathBuf) -> String {
 
 }
 
+/// 在 `index_dir` 中新建内容索引并写入 `files`，每索引一个文件回调一次已索引的文件数
+fn write_content_index(
+    index_dir: &Path,
+    files: &[(PathBuf, String)],
+    settings: &IndexingSettings,
+    mut progress: impl FnMut(usize),
+) -> Result<usize, String> {
+    fs::create_dir_all(index_dir).map_err(|e| format!("create index dir error: {}", e))?;
+    let schema = build_schema(settings);
+    let index = tantivy::Index::create_in_dir(index_dir, schema.clone())
+        .map_err(|e| format!("create index error: {}", e))?;
+    register_tokenizers_for(&index, settings);
+    let mut writer: tantivy::IndexWriter = index
+        .writer(50_000_000)
+        .map_err(|e| format!("writer error: {}", e))?;
+    let fields = index_fields(&schema)?;
+    let mut indexed = 0usize;
+    for (path, root) in files.iter() {
+        for doc in make_docs(path, root, &fields, settings) {
+            writer
+                .add_document(doc)
+                .map_err(|e| format!("add document error: {}", e))?;
+        }
+        indexed += 1;
+        progress(indexed);
+    }
+    writer
+        .commit()
+        .map_err(|e| format!("commit error: {}", e))?;
+    // 等待合并完成并释放索引文件，之后才能切换并删除原索引
+    writer
+        .wait_merging_threads()
+        .map_err(|e| format!("commit error: {}", e))?;
+    Ok(indexed)
+}
+
+/// 重建内容索引：后台线程提取文件内容，通过 `index-progress` 事件通知前端进度，失败时事件中带有错误信息
+///
+/// 新索引建在新的版本目录中，完成后再切换，重建期间仍可搜索原索引；已有重建在进行时返回错误，不再启动新的重建
 pub fn do_rebuild_index(
     app: AppHandle,
     directories: Vec<DirectoryConfigCmd>,
 ) -> Result<(), String> {
-    let app = app.clone();
+    let Some(guard) = app.state::<IndexState>().rebuild.try_start() else {
+        return Err("index rebuild already in progress".to_string());
+    };
     thread::spawn(move || {
         let index_dir = app_index_dir(&app);
-        let _ = fs::create_dir_all(&index_dir);
         let files = collect_files_from_dirs(directories);
         let total = files.len();
         emit_index_progress(&app, true, 0, total, 0, 0, 0);
 
-        let schema = build_schema();
-        let _ = fs::remove_dir_all(&index_dir);
-        let _ = fs::create_dir_all(&index_dir);
-        let index = tantivy::Index::create_in_dir(&index_dir, schema.clone()).unwrap();
-        register_tokenizers_for(&index);
-        let mut writer = index.writer(50_000_000).unwrap();
-        let (title, content, file_path, file_type, modified_time, file_size) =
-            index_fields(&schema);
-        let mut indexed = 0usize;
-        for path in files.iter() {
-            let doc = make_doc(
-                path,
-                title,
-                content,
-                file_path,
-                file_type,
-                modified_time,
-                file_size,
-            );
-            let _ = writer.add_document(doc);
-            indexed += 1;
-            let progress = if total == 0 {
-                100
-            } else {
-                ((indexed as f32 / total as f32) * 100.0).round() as u32
-            };
-            emit_index_progress(&app, true, progress, total, indexed, 0, 0);
-        }
-        let _ = writer.commit();
-        let size = compute_dir_size(&index_dir);
-        emit_index_progress(
-            &app,
-            false,
-            100,
-            total,
-            indexed,
-            size,
-            chrono::Utc::now().timestamp_millis(),
-        );
+        let settings = load_settings(&app).indexing;
+        let result = build_index_version(&index_dir, |dir| {
+            let indexed = write_content_index(dir, &files, &settings, |indexed| {
+                let progress = ((indexed as f32 / total as f32) * 100.0).round() as u32;
+                emit_index_progress(&app, true, progress, total, indexed, 0, 0);
+            })?;
+            Ok((indexed, compute_dir_size(dir)))
+        });
+        // 先清除重建状态再通知前端完成，前端收到通知后即可再次重建；重建中 panic 时 guard 同样清除重建状态
+        drop(guard);
+        match result {
+            Ok((indexed, size)) => emit_index_progress(
+                &app,
+                false,
+                100,
+                total,
+                indexed,
+                size,
+                chrono::Utc::now().timestamp_millis(),
+            ),
+            Err(e) => emit_index_error(&app, total, e),
+        }
     });
     Ok(())
 }
 
 /// 递归计算目录大小（单位：字节）
-fn compute_dir_size(dir: &PathBuf) -> u64 {
+fn compute_dir_size(dir: &Path) -> u64 {
     let mut size = 0u64;
     if let Ok(rd) = fs::read_dir(dir) {
         for e in rd.flatten() {
@@ -464,6 +1154,7 @@ fn compute_dir_size(dir: &PathBuf) -> u64 {
 mod tests {
     use super::*;
     use std::io::Write;
+    use tantivy::schema::Value;
     use zip::write::FileOptions;
     use zip::CompressionMethod;
 
@@ -548,13 +1239,37 @@ mod tests {
     fn test_read_xlsx() {
         let tmp = std::env::temp_dir().join("test_xlsx_read.xlsx");
         write_xlsx(&tmp, "Hello", "路由器");
-        let s = read_excel(&tmp);
+        let s: String = read_excel(&tmp, "xlsx").into_iter().map(|(_, t)| t).collect();
         assert!(s.contains("Hello"));
         assert!(s.contains("路由器"));
         println!("{}", s);
         let _ = fs::remove_file(&tmp);
     }
 
+    #[test]
+    fn test_read_pptx() {
+        let tmp = std::env::temp_dir().join("test_pptx_read.pptx");
+        let file = fs::File::create(&tmp).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        for (no, text) in [(2, "第二页"), (1, "Hello 路由器"), (10, "最后")] {
+            let xml = format!(
+                "<p:sld xmlns:a=\"a\" xmlns:p=\"p\"><p:cSld><p:spTree><p:sp><p:txBody>\
+                <a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>",
+                text
+            );
+            zip.start_file(format!("ppt/slides/slide{}.xml", no), options).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        let slides = read_pptx(&tmp);
+        assert_eq!(slides.len(), 3);
+        assert!(slides[0].contains("路由器"));
+        assert!(slides[1].contains("第二页"));
+        assert!(slides[2].contains("最后"));
+        let _ = fs::remove_file(&tmp);
+    }
+
     #[test]
     fn test_read_pdf() {
         let path = PathBuf::from("test.pdf");
@@ -616,6 +1331,98 @@ mod tests {
         let _ = fs::remove_file(&tmp);
     }
 
+    #[test]
+    fn test_read_line_chunks() {
+        let tmp = std::env::temp_dir().join("test_line_chunks.log");
+        let lines: Vec<String> = (1..=25).map(|i| format!("line {}", i)).collect();
+        fs::write(&tmp, lines.join("\n")).unwrap();
+        let chunks = read_line_chunks(&tmp, 10);
+        assert_eq!(chunks.len(), 3);
+        let starts: Vec<u64> = chunks.iter().filter_map(|c| c.line_start).collect();
+        assert_eq!(starts, vec![1, 11, 21]);
+        assert_eq!(chunks[2].text, "line 21\nline 22\nline 23\nline 24\nline 25");
+        let _ = fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_split_text_passages() {
+        let text = "# 第一章\n一二三四五\n六七八九十\n# 第二章\n甲乙丙丁戊\n";
+        let passages = split_text_passages(text, 10, true);
+        assert_eq!(passages.len(), 3);
+        assert_eq!(passages[0].name.as_deref(), Some("第一章"));
+        assert_eq!(passages[1].name.as_deref(), Some("第一章"));
+        assert_eq!(passages[2].name.as_deref(), Some("第二章"));
+        let joined: String = passages.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn test_write_content_index() {
+        let root = std::env::temp_dir().join("test_write_content_index");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let files: Vec<(PathBuf, String)> = ["a.txt", "b.md"]
+            .iter()
+            .map(|name| {
+                let path = root.join(name);
+                fs::write(&path, "路由器配置").unwrap();
+                (path, root.to_string_lossy().to_string())
+            })
+            .collect();
+        let base = root.join("index");
+        let mut seen = Vec::new();
+        let indexed = build_index_version(&base, |dir| {
+            write_content_index(dir, &files, &IndexingSettings::default(), |n| seen.push(n))
+        })
+        .unwrap();
+        assert_eq!(indexed, 2);
+        assert_eq!(seen, vec![1, 2]);
+        let index = tantivy::Index::open_in_dir(crate::index_store::live_index_dir(&base)).unwrap();
+        let reader = index.reader().unwrap();
+        assert_eq!(reader.searcher().num_docs(), 2);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_make_docs_splits_long_text() {
+        let tmp = std::env::temp_dir().join("test_make_docs_passages.txt");
+        fs::write(&tmp, "路由器配置\n".repeat(100)).unwrap();
+        let schema = build_schema(&IndexingSettings::default());
+        let fields = index_fields(&schema).unwrap();
+        let settings = IndexingSettings {
+            passage_size: 120,
+            ..Default::default()
+        };
+        let docs = make_docs(&tmp, "", &fields, &settings);
+        // 父文档 + 5 个段落
+        assert_eq!(docs.len(), 6);
+        assert!(docs[0].get_first(fields.content).is_none());
+        assert_eq!(docs[1].get_first(fields.passage_no).and_then(|v| v.as_u64()), Some(1));
+        assert!(docs[1].get_first(fields.title).is_none());
+        let _ = fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_make_docs_nfc_metadata() {
+        // front matter 和标题中的 NFD 文本（e 加组合重音符）统一为 NFC
+        let tmp = std::env::temp_dir().join("test_make_docs_nfc.md");
+        let nfd = "Cafe\u{301}";
+        fs::write(&tmp, format!("---\ntitle: {0}\ntags: [{0}]\n---\n# {0}\n正文\n", nfd)).unwrap();
+        let schema = build_schema(&IndexingSettings::default());
+        let fields = index_fields(&schema).unwrap();
+        let docs = make_docs(&tmp, "", &fields, &IndexingSettings::default());
+        let values = |field: Field| -> Vec<String> {
+            docs[0]
+                .get_all(field)
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        };
+        assert_eq!(values(fields.meta_title), vec!["Café"]);
+        assert_eq!(values(fields.tags), vec!["café"]);
+        assert_eq!(values(fields.headings), vec!["Café"]);
+        let _ = fs::remove_file(&tmp);
+    }
+
     #[test]
     fn test_read_doc_local() {
         let path = PathBuf::from("test.doc");
@@ -0,0 +1,182 @@
+use jieba_rs::Jieba;
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+use crate::zh_normalize::normalize_zh_with_offsets;
+
+/// 用户词典的路径及修改时间，不使用用户词典时为 None
+type DictKey = Option<(String, Option<SystemTime>)>;
+
+/// 已加载的分词词典，按用户词典路径及其修改时间缓存（默认词典加载较慢，只加载一次），
+/// 用户词典修改后重新加载
+static JIEBA_CACHE: LazyLock<Mutex<HashMap<DictKey, Arc<Jieba>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 加载 jieba 默认词典，并合并用户词典（每行 `词语 [词频] [词性]`），用户词典读取失败时忽略
+pub fn load_jieba(user_dict: Option<&str>) -> Arc<Jieba> {
+    let key: DictKey = user_dict.filter(|s| !s.trim().is_empty()).map(|p| {
+        let modified = std::fs::metadata(p).and_then(|m| m.modified()).ok();
+        (p.to_string(), modified)
+    });
+    let mut cache = JIEBA_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(jieba) = cache.get(&key) {
+        return jieba.clone();
+    }
+    // 同一用户词典的旧版本不再使用
+    if let Some((path, _)) = &key {
+        cache.retain(|k, _| k.as_ref().is_none_or(|(p, _)| p != path));
+    }
+    let mut jieba = Jieba::new();
+    if let Some(f) = key.as_ref().and_then(|(p, _)| std::fs::File::open(p).ok()) {
+        let _ = jieba.load_dict(&mut BufReader::new(f));
+    }
+    let jieba = Arc::new(jieba);
+    cache.insert(key, jieba.clone());
+    jieba
+}
+
+/// 基于词典的中文分词器，中英文混排时英文单词和数字保持完整
+///
+/// 与 jieba 的搜索模式相同，长词额外输出词典中的 2、3 字子词（如“北京大学”输出“北京”“大学”），
+/// 子词与所在的词位置相同，短语查询时不会错位
+#[derive(Clone)]
+pub struct JiebaTokenizer {
+    jieba: Arc<Jieba>,
+    tokens: Vec<Token>,
+}
+
+impl JiebaTokenizer {
+    pub fn new(jieba: Arc<Jieba>) -> Self {
+        Self {
+            jieba,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+pub struct JiebaTokenStream<'a> {
+    tokens: &'a mut Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for JiebaTokenizer {
+    type TokenStream<'a> = JiebaTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> JiebaTokenStream<'a> {
+        self.tokens.clear();
+        // 在繁简、全半角归一后的文本上分词，繁体和全角文本也能按词典切分，偏移映射回原文
+        let (normalized, offsets) = normalize_zh_with_offsets(text);
+        let mut offset = 0usize;
+        let mut position = 0usize;
+        for word in self.jieba.cut(&normalized, true) {
+            let start = offset;
+            offset += word.len();
+            // 跳过空白和标点
+            if !word.chars().any(|c| c.is_alphanumeric()) {
+                continue;
+            }
+            let chars: Vec<(usize, char)> = word.char_indices().collect();
+            for n in [2usize, 3] {
+                if chars.len() <= n {
+                    continue;
+                }
+                for i in 0..=chars.len() - n {
+                    let from = chars[i].0;
+                    let to = chars.get(i + n).map(|c| c.0).unwrap_or(word.len());
+                    if self.jieba.has_word(&word[from..to]) {
+                        self.tokens.push(Token {
+                            offset_from: offsets[start + from],
+                            offset_to: offsets[start + to],
+                            position,
+                            text: word[from..to].to_string(),
+                            position_length: 1,
+                        });
+                    }
+                }
+            }
+            self.tokens.push(Token {
+                offset_from: offsets[start],
+                offset_to: offsets[start + word.len()],
+                position,
+                text: word.to_string(),
+                position_length: 1,
+            });
+            position += 1;
+        }
+        JiebaTokenStream {
+            tokens: &mut self.tokens,
+            index: 0,
+        }
+    }
+}
+
+impl TokenStream for JiebaTokenStream<'_> {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zh_normalize::normalize_zh;
+
+    fn tokens(text: &str) -> Vec<(String, usize)> {
+        let mut tokenizer = JiebaTokenizer::new(load_jieba(None));
+        let mut stream = tokenizer.token_stream(text);
+        let mut out = Vec::new();
+        while let Some(t) = stream.next() {
+            assert_eq!(normalize_zh(&text[t.offset_from..t.offset_to]), t.text);
+            out.push((t.text.clone(), t.position));
+        }
+        out
+    }
+
+    #[test]
+    fn test_jieba_tokenizer() {
+        let toks = tokens("我在北京大学学习Rust 2024。");
+        let words: Vec<&str> = toks.iter().map(|(w, _)| w.as_str()).collect();
+        assert!(words.contains(&"北京大学"));
+        assert!(words.contains(&"北京"));
+        assert!(words.contains(&"Rust"));
+        assert!(words.contains(&"2024"));
+        assert!(!words.contains(&"京大"));
+        assert!(!words.iter().any(|w| w.trim().is_empty() || *w == "。"));
+        // 子词与所在的词位置相同
+        let pos = |w: &str| toks.iter().find(|(t, _)| t == w).unwrap().1;
+        assert_eq!(pos("北京"), pos("北京大学"));
+        // 全角字母数字归一后与半角一致
+        assert!(tokens("型号ＡＢＣ１２３").iter().any(|(w, _)| w == "ABC123"));
+    }
+
+    #[test]
+    fn test_reload_user_dictionary() {
+        let dict = std::env::temp_dir().join("test_jieba_user_dict.txt");
+        std::fs::write(&dict, "量子纠缠态 100\n").unwrap();
+        let path = dict.to_string_lossy().to_string();
+        assert!(load_jieba(Some(&path)).has_word("量子纠缠态"));
+        // 修改用户词典后重新加载
+        std::fs::write(&dict, "光子晶体管 100\n").unwrap();
+        let f = std::fs::File::options().write(true).open(&dict).unwrap();
+        f.set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        assert!(load_jieba(Some(&path)).has_word("光子晶体管"));
+        let _ = std::fs::remove_file(&dict);
+    }
+}
@@ -1,9 +1,33 @@
+mod collector;
+mod csv_reader;
+mod file_detect;
+mod file_types;
+mod filename_index;
+mod filename_tokenizer;
+mod image_meta;
+mod index_store;
 mod indexer;
+mod jieba_tokenizer;
+mod markdown;
+mod media_meta;
+mod mixed_tokenizer;
+mod pinyin_tokenizer;
+mod query_builder;
+mod query_syntax;
+mod regex_search;
 mod search;
+mod settings;
+mod symbols;
 mod types;
+mod unicode_norm;
+mod zh_normalize;
 use std::error::Error;
 
-use crate::types::{DirectoryConfigCmd, SearchFiltersCmd, SearchResponsePayload};
+use crate::file_types::FileTypeGroup;
+use crate::types::{
+    DirectoryConfigCmd, FilenameSearchResponsePayload, SearchErrorPayload, SearchFiltersCmd,
+    SearchResponsePayload, SearchSort,
+};
 use tauri::menu::{AboutMetadataBuilder, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::Manager;
 use rust_i18n::t;
@@ -15,14 +39,31 @@ rust_i18n::i18n!("locales", fallback = "zh-CN");
 
 /// 搜索索引: 基于已有索引返回匹配文档
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 fn search_index(
     app: tauri::AppHandle,
     query: String,
     limit: Option<usize>,
     offset: Option<usize>,
     filters: Option<SearchFiltersCmd>,
-) -> Result<SearchResponsePayload, String> {
-    crate::search::do_search_index(app, query, limit, offset, filters)
+    sort: Option<SearchSort>,
+    facets: Option<bool>,
+    match_precision: Option<f32>,
+    fuzzy: Option<u8>,
+    regex: Option<bool>,
+) -> Result<SearchResponsePayload, SearchErrorPayload> {
+    crate::search::do_search_index(
+        app,
+        query,
+        limit,
+        offset,
+        filters,
+        sort.unwrap_or_default(),
+        facets.unwrap_or(false),
+        match_precision.unwrap_or(1.0),
+        fuzzy.unwrap_or(0),
+        regex.unwrap_or(false),
+    )
 }
 
 /// 重建索引: 前端调用该命令触发索引重建
@@ -34,6 +75,33 @@ fn rebuild_index(
     crate::indexer::do_rebuild_index(app, directories)
 }
 
+/// 重建文件名索引: 快速遍历目录，只索引所有文件的文件名和路径，不提取内容
+#[tauri::command]
+fn rebuild_filename_index(
+    app: tauri::AppHandle,
+    directories: Vec<DirectoryConfigCmd>,
+) -> Result<(), String> {
+    crate::filename_index::do_rebuild_filename_index(app, directories)
+}
+
+/// 文件名搜索: 在文件名索引中按前缀和子串匹配文件名或路径
+#[tauri::command]
+fn search_filenames(
+    app: tauri::AppHandle,
+    query: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<FilenameSearchResponsePayload, String> {
+    crate::filename_index::do_search_filenames(app, query, limit, offset)
+}
+
+/// 文件类型分组: 返回内置分组及设置中的自定义分组，前端据此生成文件类型筛选项
+#[tauri::command]
+fn list_file_type_groups(app: tauri::AppHandle) -> Vec<FileTypeGroup> {
+    let settings = crate::settings::load_settings(&app);
+    crate::file_types::file_type_groups(&settings.search.file_type_groups)
+}
+
 #[tauri::command]
 fn update_menu(app: tauri::AppHandle, lang: String) -> Result<(), String> {
     rust_i18n::set_locale(&lang);
@@ -199,9 +267,14 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(crate::indexer::IndexState::default())
+        .manage(crate::filename_index::FilenameIndexState::default())
         .invoke_handler(tauri::generate_handler![
             rebuild_index,
             search_index,
+            rebuild_filename_index,
+            search_filenames,
+            list_file_type_groups,
             update_menu
         ])
         .setup(setup)
@@ -0,0 +1,238 @@
+/// Markdown 文档解析结果：front matter 元数据、正文与标题
+#[derive(Default, Debug)]
+pub struct MarkdownDoc {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    /// front matter 中的日期（毫秒时间戳）
+    pub date: Option<i64>,
+    /// 去掉 front matter 后的正文
+    pub body: String,
+    pub headings: Vec<String>,
+}
+
+/// 解析 Markdown 文本，支持 YAML（---）和 TOML（+++）两种 front matter
+pub fn parse_markdown(text: &str) -> MarkdownDoc {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut doc = MarkdownDoc::default();
+    let body = match split_front_matter(text) {
+        Some((fence, fm, body)) => {
+            let meta = if fence == "---" {
+                serde_yaml::from_str::<serde_yaml::Value>(fm)
+                    .ok()
+                    .and_then(|v| serde_json::to_value(v).ok())
+            } else {
+                toml::from_str::<toml::Table>(fm)
+                    .ok()
+                    .and_then(|v| serde_json::to_value(v).ok())
+            };
+            if let Some(meta) = meta {
+                apply_front_matter(&mut doc, &meta);
+            }
+            body
+        }
+        None => text,
+    };
+    let mut scanner = HeadingScanner::default();
+    doc.headings = body
+        .lines()
+        .filter_map(|l| scanner.heading(l))
+        .map(|h| h.to_string())
+        .collect();
+    doc.body = body.to_string();
+    doc
+}
+
+/// 查找正文中位于 `byte_pos` 之前最近的标题
+pub fn nearest_heading(body: &str, byte_pos: usize) -> Option<String> {
+    let mut found = None;
+    let mut offset = 0usize;
+    let mut scanner = HeadingScanner::default();
+    for line in body.split_inclusive('\n') {
+        if offset > byte_pos {
+            break;
+        }
+        if let Some(h) = scanner.heading(line) {
+            found = Some(h.to_string());
+        }
+        offset += line.len();
+    }
+    found
+}
+
+/// 拆分 front matter，返回（分隔符、front matter 内容、正文）
+fn split_front_matter(text: &str) -> Option<(&'static str, &str, &str)> {
+    let fence = if text.starts_with("---") {
+        "---"
+    } else if text.starts_with("+++") {
+        "+++"
+    } else {
+        return None;
+    };
+    let first_nl = text.find('\n')?;
+    if text[..first_nl].trim_end() != fence {
+        return None;
+    }
+    let rest = &text[first_nl + 1..];
+    let mut offset = 0usize;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == fence {
+            return Some((fence, &rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn apply_front_matter(doc: &mut MarkdownDoc, meta: &serde_json::Value) {
+    doc.title = meta
+        .get("title")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    doc.tags = match meta.get("tags").or_else(|| meta.get("keywords")) {
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(serde_json::Value::String(s)) => s
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    };
+    doc.date = meta.get("date").and_then(parse_date_value);
+}
+
+/// 解析 front matter 中的日期，支持 RFC3339、`YYYY-MM-DD HH:MM:SS` 与 `YYYY-MM-DD`
+fn parse_date_value(v: &serde_json::Value) -> Option<i64> {
+    // TOML 的日期类型序列化后为 {"$__toml_private_datetime": "..."}
+    let s = match v {
+        serde_json::Value::String(s) => s.as_str(),
+        serde_json::Value::Object(m) => m.values().next()?.as_str()?,
+        _ => return None,
+    };
+    let s = s.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_millis());
+    }
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Some(dt.and_utc().timestamp_millis());
+    }
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
+        return Some(dt.and_utc().timestamp_millis());
+    }
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
+
+/// 逐行识别标题，跳过围栏代码块（``` 或 ~~~）中以 `#` 开头的行（如脚本注释）
+#[derive(Default)]
+pub struct HeadingScanner {
+    /// 所在代码块的围栏字符及长度
+    fence: Option<(char, usize)>,
+}
+
+impl HeadingScanner {
+    pub fn heading<'a>(&mut self, line: &'a str) -> Option<&'a str> {
+        if let Some((c, n)) = fence_marker(line) {
+            match self.fence {
+                None => {
+                    self.fence = Some((c, n));
+                    return None;
+                }
+                // 结束围栏：相同字符、不短于开始围栏，且后面没有其他内容
+                Some((fc, fnum)) if c == fc && n >= fnum && line.trim().chars().all(|x| x == c) => {
+                    self.fence = None;
+                    return None;
+                }
+                _ => {}
+            }
+        }
+        if self.fence.is_some() {
+            return None;
+        }
+        heading_text(line)
+    }
+}
+
+/// 围栏代码块的开始或结束行（至少 3 个 ` 或 ~），返回围栏字符及长度
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let t = line.trim_start();
+    let c = t.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let n = t.chars().take_while(|&x| x == c).count();
+    (n >= 3).then_some((c, n))
+}
+
+/// ATX 标题（`# 标题`），返回去掉 `#` 后的标题文本
+fn heading_text(line: &str) -> Option<&str> {
+    let line = line.trim_end();
+    let trimmed = line.trim_start_matches('#');
+    let level = line.len() - trimmed.len();
+    if level == 0 || level > 6 || !(trimmed.is_empty() || trimmed.starts_with(' ')) {
+        return None;
+    }
+    let h = trimmed.trim().trim_end_matches('#').trim();
+    if h.is_empty() {
+        None
+    } else {
+        Some(h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yaml_front_matter() {
+        let md = "---\ntitle: 路由器配置\ntags: [network, 路由]\ndate: 2024-06-01\n---\n# 简介\n正文\n## 步骤\n内容";
+        let doc = parse_markdown(md);
+        assert_eq!(doc.title.as_deref(), Some("路由器配置"));
+        assert_eq!(doc.tags, vec!["network", "路由"]);
+        assert!(doc.date.is_some());
+        assert_eq!(doc.headings, vec!["简介", "步骤"]);
+        assert!(!doc.body.contains("title:"));
+    }
+
+    #[test]
+    fn test_parse_toml_front_matter() {
+        let md = "+++\ntitle = \"Notes\"\ntags = \"a, b\"\ndate = 2024-06-01T10:00:00Z\n+++\nbody";
+        let doc = parse_markdown(md);
+        assert_eq!(doc.title.as_deref(), Some("Notes"));
+        assert_eq!(doc.tags, vec!["a", "b"]);
+        assert!(doc.date.is_some());
+        assert_eq!(doc.body, "body");
+    }
+
+    #[test]
+    fn test_nearest_heading() {
+        let body = "# 简介\n正文\n## 步骤\n打开路由器\n";
+        let pos = body.find("路由器").unwrap();
+        assert_eq!(nearest_heading(body, pos).as_deref(), Some("步骤"));
+        assert_eq!(nearest_heading(body, 0).as_deref(), Some("简介"));
+        assert_eq!(nearest_heading("no heading", 3), None);
+    }
+
+    #[test]
+    fn test_fenced_code_not_heading() {
+        let md = "# 安装
+```bash
+# 安装依赖
+npm install
+```
+正文
+~~~
+# comment
+~~~
+## 使用
+";
+        assert_eq!(parse_markdown(md).headings, vec!["安装", "使用"]);
+        let pos = md.find("正文").unwrap();
+        assert_eq!(nearest_heading(md, pos).as_deref(), Some("安装"));
+    }
+}
@@ -0,0 +1,170 @@
+use std::{fs, path::PathBuf};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+use symphonia::core::units::TimeBase;
+
+/// 音视频元数据（ID3、Vorbis comment、MP4 atom 等标签与时长）
+#[derive(Default, Debug)]
+pub struct MediaMeta {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub year: Option<u64>,
+    pub genre: Option<String>,
+    /// 时长（秒）
+    pub duration: Option<u64>,
+}
+
+/// 支持读取标签的音频扩展名，同时作为文件类型筛选中 `audio` 分组的扩展名
+pub const AUDIO_EXTS: &[&str] = &["mp3", "flac", "ogg", "oga", "opus", "m4a", "wav"];
+
+/// 支持读取标签的视频扩展名，同时作为文件类型筛选中 `video` 分组的扩展名
+pub const VIDEO_EXTS: &[&str] = &["mp4", "m4v", "mov", "mkv", "webm"];
+
+pub fn is_audio_ext(ext: &str) -> bool {
+    AUDIO_EXTS.contains(&ext)
+}
+
+pub fn is_video_ext(ext: &str) -> bool {
+    VIDEO_EXTS.contains(&ext)
+}
+
+/// 读取音视频文件的标签和时长，无法识别的文件返回空元数据
+pub fn read_media_meta(path: &PathBuf) -> MediaMeta {
+    let mut meta = MediaMeta::default();
+    let Ok(file) = fs::File::open(path) else {
+        return meta;
+    };
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+        hint.with_extension(ext);
+    }
+    let Ok(mut probed) = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) else {
+        return meta;
+    };
+    // 容器之前的标签（如 MP3 的 ID3v2）由探测阶段读取，容器内的标签优先
+    if let Some(rev) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        apply_tags(&mut meta, rev.tags());
+    }
+    if let Some(rev) = probed.format.metadata().current() {
+        apply_tags(&mut meta, rev.tags());
+    }
+    meta.duration = probed
+        .format
+        .tracks()
+        .iter()
+        .filter_map(|t| {
+            let p = &t.codec_params;
+            let tb = p
+                .time_base
+                .or_else(|| p.sample_rate.map(|r| TimeBase::new(1, r)))?;
+            Some(tb.calc_time(p.n_frames?).seconds)
+        })
+        .max();
+    meta
+}
+
+fn apply_tags(meta: &mut MediaMeta, tags: &[Tag]) {
+    for tag in tags {
+        // RIFF INFO、ID3 等标签的字符串可能带有结尾的 NUL 填充
+        let value = tag
+            .value
+            .to_string()
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => meta.title = Some(value),
+            Some(StandardTagKey::Artist) => meta.artist = Some(value),
+            // 没有艺术家时使用专辑艺术家
+            Some(StandardTagKey::AlbumArtist) if meta.artist.is_none() => {
+                meta.artist = Some(value)
+            }
+            Some(StandardTagKey::Album) => meta.album = Some(value),
+            Some(StandardTagKey::Genre) => meta.genre = Some(value),
+            Some(StandardTagKey::Date | StandardTagKey::OriginalDate) => {
+                meta.year = parse_year(&value).or(meta.year)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 从 `2019`、`2019-05-01` 等日期文本中取出年份
+fn parse_year(s: &str) -> Option<u64> {
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.len() != 4 {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 生成带 INFO 标签的单声道 8 位 WAV 文件
+    fn write_wav(path: &PathBuf, seconds: u32) {
+        let rate = 8000u32;
+        let mut info = b"INFO".to_vec();
+        for (id, v) in [(b"INAM", "夜曲"), (b"IART", "Jay Chou"), (b"ICRD", "2006-11-01")] {
+            let mut v = v.as_bytes().to_vec();
+            v.push(0);
+            if v.len() % 2 == 1 {
+                v.push(0);
+            }
+            info.extend_from_slice(id);
+            info.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            info.extend_from_slice(&v);
+        }
+        let data_len = rate * seconds;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        let riff_len = 4 + (8 + 16) + (8 + info.len() as u32) + (8 + data_len);
+        wav.extend_from_slice(&riff_len.to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&rate.to_le_bytes());
+        wav.extend_from_slice(&rate.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&8u16.to_le_bytes());
+        wav.extend_from_slice(b"LIST");
+        wav.extend_from_slice(&(info.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&info);
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(wav.len() + data_len as usize, 0x80);
+        fs::write(path, wav).unwrap();
+    }
+
+    #[test]
+    fn test_read_media_meta_wav() {
+        let tmp = std::env::temp_dir().join("test_media_meta.wav");
+        write_wav(&tmp, 3);
+        let meta = read_media_meta(&tmp);
+        assert_eq!(meta.title.as_deref(), Some("夜曲"));
+        assert_eq!(meta.artist.as_deref(), Some("Jay Chou"));
+        assert_eq!(meta.year, Some(2006));
+        assert_eq!(meta.duration, Some(3));
+        let _ = fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_parse_year() {
+        assert_eq!(parse_year("2019"), Some(2019));
+        assert_eq!(parse_year("2019-05-01T00:00:00Z"), Some(2019));
+        assert_eq!(parse_year("19"), None);
+    }
+}
@@ -0,0 +1,187 @@
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, Language, LowerCaser, Stemmer, StopWordFilter, TextAnalyzer, Token,
+    TokenStream, Tokenizer,
+};
+
+use crate::zh_normalize::ZhNormalizer;
+
+/// 支持的文本语言：设置中的取值（同时用作分词器名称的后缀）及对应的词干提取语言
+pub const TEXT_LANGUAGES: &[(&str, Language)] = &[
+    ("english", Language::English),
+    ("french", Language::French),
+    ("german", Language::German),
+    ("spanish", Language::Spanish),
+    ("italian", Language::Italian),
+    ("portuguese", Language::Portuguese),
+    ("dutch", Language::Dutch),
+    ("swedish", Language::Swedish),
+    ("russian", Language::Russian),
+];
+
+/// 设置中的文本语言，未设置或不支持时返回 None（按 n-gram 分词）
+pub fn text_language(name: Option<&str>) -> Option<&'static str> {
+    let name = name?.trim().to_lowercase();
+    TEXT_LANGUAGES
+        .iter()
+        .find(|(code, _)| *code == name)
+        .map(|(code, _)| *code)
+}
+
+/// 中日韩文字（汉字、假名、韩文音节）
+pub fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+            | 0x20000..=0x2FA1F
+    )
+}
+
+/// 中英混排分词器：连续的中日韩文字按 n-gram 切分，其余连续的字母数字按单词切分，标点和空白作为分隔
+///
+/// 与 n-gram 分词器相同，所有词项的位置均为 0
+#[derive(Clone)]
+pub struct MixedTokenizer {
+    min_gram: usize,
+    max_gram: usize,
+    tokens: Vec<Token>,
+}
+
+impl MixedTokenizer {
+    pub fn new(min_gram: usize, max_gram: usize) -> Self {
+        Self {
+            min_gram,
+            max_gram,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+/// 依次输出分词器预先切分好的词项，供混合分词器和文件名分词器共用
+pub struct VecTokenStream<'a> {
+    tokens: &'a mut Vec<Token>,
+    index: usize,
+}
+
+impl<'a> VecTokenStream<'a> {
+    pub fn new(tokens: &'a mut Vec<Token>) -> Self {
+        Self { tokens, index: 0 }
+    }
+}
+
+impl Tokenizer for MixedTokenizer {
+    type TokenStream<'a> = VecTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> VecTokenStream<'a> {
+        self.tokens.clear();
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let byte = |k: usize| chars.get(k).map(|c| c.0).unwrap_or(text.len());
+        let push = |tokens: &mut Vec<Token>, from: usize, to: usize| {
+            tokens.push(Token {
+                offset_from: from,
+                offset_to: to,
+                position: 0,
+                text: text[from..to].to_string(),
+                position_length: 1,
+            });
+        };
+        let mut i = 0usize;
+        while i < chars.len() {
+            let cjk = is_cjk(chars[i].1);
+            if !cjk && !chars[i].1.is_alphanumeric() {
+                i += 1;
+                continue;
+            }
+            let mut j = i + 1;
+            while j < chars.len() {
+                let c = chars[j].1;
+                if cjk != is_cjk(c) || !(cjk || c.is_alphanumeric()) {
+                    break;
+                }
+                j += 1;
+            }
+            if cjk {
+                for s in i..j {
+                    for n in self.min_gram..=self.max_gram.min(j - s) {
+                        push(&mut self.tokens, byte(s), byte(s + n));
+                    }
+                }
+            } else {
+                push(&mut self.tokens, byte(i), byte(j));
+            }
+            i = j;
+        }
+        VecTokenStream::new(&mut self.tokens)
+    }
+}
+
+impl TokenStream for VecTokenStream<'_> {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+/// 在分词器之后依次做繁简和全半角归一、小写、去停用词、去除重音符号和词干提取，
+/// 后三步只影响拉丁字母等单词，中文词项保持不变
+///
+/// 停用词表中含重音符号（如法语“à”“été”），须在去除重音符号之前过滤；
+/// 先去除重音符号再提取词干，“résumé”与“resume”得到相同的词干
+pub fn text_analyzer<T: Tokenizer>(tokenizer: T, language: Language) -> TextAnalyzer {
+    let mut builder = TextAnalyzer::builder(tokenizer)
+        .filter(ZhNormalizer)
+        .filter(LowerCaser)
+        .dynamic();
+    if let Some(stop_words) = StopWordFilter::new(language) {
+        builder = builder.filter_dynamic(stop_words);
+    }
+    builder
+        .filter_dynamic(AsciiFoldingFilter)
+        .filter_dynamic(Stemmer::new(language))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<String> {
+        let mut analyzer = text_analyzer(MixedTokenizer::new(2, 3), Language::English);
+        let mut stream = analyzer.token_stream(text);
+        let mut out = Vec::new();
+        while let Some(t) = stream.next() {
+            out.push(t.text.clone());
+        }
+        out
+    }
+
+    #[test]
+    fn test_mixed_tokenizer() {
+        assert_eq!(
+            tokens("服务器Configuration的résumé, and V2"),
+            vec!["服务", "服务器", "务器", "configur", "resum", "v2"]
+        );
+        assert_eq!(tokens("configure"), tokens("Configuration"));
+        // 带重音符号的停用词也被去除
+        let mut analyzer = text_analyzer(MixedTokenizer::new(2, 3), Language::French);
+        let mut stream = analyzer.token_stream("était à Paris");
+        let mut french = Vec::new();
+        while let Some(t) = stream.next() {
+            french.push(t.text.clone());
+        }
+        assert_eq!(french, vec!["paris"]);
+        assert_eq!(text_language(Some("English")), Some("english"));
+        assert_eq!(text_language(Some("")), None);
+    }
+}
@@ -0,0 +1,194 @@
+use pinyin::ToPinyin;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+/// 每个拼音词项最多包含的汉字数
+const MAX_PINYIN_CHARS: usize = 8;
+
+/// 汉字的全拼和首字母
+type CharPinyin = Option<(&'static str, &'static str)>;
+
+/// 汉字的全拼和首字母，非汉字返回 None
+fn char_pinyin(c: char) -> CharPinyin {
+    c.to_pinyin().map(|p| (p.plain(), p.first_letter()))
+}
+
+/// 拼音分词器：从每个汉字开始，输出其后连续汉字（最多 8 个）的全拼和首字母词项，
+/// 如“路由器”输出 `luyouqi`、`lyq`、`youqi`、`yq`、`qi`、`q`，查询时按前缀匹配即可匹配任意位置开始的拼音
+///
+/// 词项的偏移指向原始汉字，非汉字字符被忽略
+#[derive(Clone, Default)]
+pub struct PinyinTokenizer {
+    tokens: Vec<Token>,
+}
+
+pub struct PinyinTokenStream<'a> {
+    tokens: &'a mut Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for PinyinTokenizer {
+    type TokenStream<'a> = PinyinTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> PinyinTokenStream<'a> {
+        self.tokens.clear();
+        let chars: Vec<(usize, char, CharPinyin)> = text
+            .char_indices()
+            .map(|(i, c)| (i, c, char_pinyin(c)))
+            .collect();
+        for (start, &(offset_from, _, py)) in chars.iter().enumerate() {
+            if py.is_none() {
+                continue;
+            }
+            let mut full = String::new();
+            let mut initials = String::new();
+            let mut offset_to = offset_from;
+            for &(i, c, py) in chars[start..].iter().take(MAX_PINYIN_CHARS) {
+                let Some((plain, first)) = py else {
+                    break;
+                };
+                full.push_str(plain);
+                initials.push_str(first);
+                offset_to = i + c.len_utf8();
+            }
+            for text in [full, initials] {
+                self.tokens.push(Token {
+                    offset_from,
+                    offset_to,
+                    position: start,
+                    text,
+                    position_length: 1,
+                });
+            }
+        }
+        PinyinTokenStream {
+            tokens: &mut self.tokens,
+            index: 0,
+        }
+    }
+}
+
+impl TokenStream for PinyinTokenStream<'_> {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+/// 首字母查询最多包含的字母数
+const MAX_INITIALS: usize = 4;
+
+/// 全部拼音音节（不带声调）及其前缀，由常用汉字的拼音汇总得到
+static SYLLABLES: LazyLock<(HashSet<&'static str>, HashSet<&'static str>)> = LazyLock::new(|| {
+    let mut syllables = HashSet::new();
+    let mut prefixes = HashSet::new();
+    for (plain, _) in ('\u{4E00}'..='\u{9FFF}').filter_map(char_pinyin) {
+        syllables.insert(plain);
+        // 拼音中可能有 `ü` 等非 ASCII 字母，按字符边界取前缀
+        for (i, c) in plain.char_indices() {
+            prefixes.insert(&plain[..i + c.len_utf8()]);
+        }
+    }
+    (syllables, prefixes)
+});
+
+/// 文本能否完整切分为拼音音节，最后一个音节可以只写开头部分
+fn is_syllables(s: &str) -> bool {
+    let (syllables, prefixes) = &*SYLLABLES;
+    if s.is_empty() || prefixes.contains(s) {
+        return true;
+    }
+    (1..=s.len().min(6)).any(|n| syllables.contains(&s[..n]) && is_syllables(&s[n..]))
+}
+
+/// 查询词是否可能是拼音：由至少 2 个小写字母组成，且能切分为拼音音节（全拼，如 `luyouq`），
+/// 或不超过 4 个字母（首字母，如 `lyq`）；`report` 等英文单词不按拼音匹配
+pub fn is_pinyin_query(term: &str) -> bool {
+    term.len() >= 2
+        && term.chars().all(|c| c.is_ascii_lowercase())
+        && (term.len() <= MAX_INITIALS || is_syllables(term))
+}
+
+/// 查找文本中拼音（全拼或首字母）以查询词开头的原始汉字片段，用于高亮，如 `lyq` 返回“路由器”
+pub fn pinyin_matches(text: &str, term: &str) -> Vec<String> {
+    let chars: Vec<(char, CharPinyin)> = text.chars().map(|c| (c, char_pinyin(c))).collect();
+    let mut found: Vec<String> = Vec::new();
+    let mut start = 0usize;
+    while start < chars.len() {
+        let mut matched = 0usize;
+        for initials in [false, true] {
+            let mut acc = String::new();
+            for (n, (_, py)) in chars[start..].iter().enumerate() {
+                let Some((plain, first)) = py else {
+                    break;
+                };
+                acc.push_str(if initials { first } else { plain });
+                if acc.len() >= term.len() {
+                    if acc.starts_with(term) {
+                        matched = matched.max(n + 1);
+                    }
+                    break;
+                }
+                if !term.starts_with(acc.as_str()) {
+                    break;
+                }
+            }
+        }
+        if matched > 0 {
+            let s: String = chars[start..start + matched].iter().map(|(c, _)| c).collect();
+            if !found.contains(&s) {
+                found.push(s);
+            }
+            start += matched;
+        } else {
+            start += 1;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinyin_tokenizer() {
+        let mut tokenizer = PinyinTokenizer::default();
+        let text = "路由器配置.docx";
+        let mut stream = tokenizer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(t) = stream.next() {
+            tokens.push((t.text.clone(), &text[t.offset_from..t.offset_to]));
+        }
+        assert_eq!(tokens[0], ("luyouqipeizhi".to_string(), "路由器配置"));
+        assert_eq!(tokens[1], ("lyqpz".to_string(), "路由器配置"));
+        assert!(tokens.contains(&("peizhi".to_string(), "配置")));
+        assert_eq!(tokens.len(), 10);
+    }
+
+    #[test]
+    fn test_pinyin_matches() {
+        assert_eq!(pinyin_matches("路由器配置.docx", "luyouqi"), vec!["路由器"]);
+        assert_eq!(pinyin_matches("路由器配置.docx", "lyq"), vec!["路由器"]);
+        assert_eq!(pinyin_matches("网络路由器配置", "peiz"), vec!["配置"]);
+        assert!(pinyin_matches("路由器配置", "beijing").is_empty());
+        assert!(is_pinyin_query("lyq"));
+        assert!(is_pinyin_query("luyouqi"));
+        assert!(is_pinyin_query("luyouq"));
+        assert!(!is_pinyin_query("report"));
+        assert!(!is_pinyin_query("路由"));
+    }
+}
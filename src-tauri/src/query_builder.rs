@@ -0,0 +1,531 @@
+use tantivy::query::{
+    AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, Query, QueryParser, RegexQuery,
+    TermQuery,
+};
+use tantivy::query_grammar::{self, Delimiter, UserInputAst, UserInputLeaf, UserInputLiteral};
+use tantivy::schema::{Field, IndexRecordOption};
+use tantivy::tokenizer::TokenStream;
+use tantivy::{Index, Score, Term};
+
+use crate::mixed_tokenizer::is_cjk;
+
+/// 查询语法中的保留字符，退回按普通词查询时当作分隔符
+const SYNTAX_CHARS: &[char] = &[
+    '+', '^', '`', ':', '{', '}', '"', '\'', '[', ']', '(', ')', '!', '\\', '*', '~',
+];
+
+/// 模糊匹配的权重，低于精确匹配
+const FUZZY_BOOST: Score = 0.5;
+
+/// 在 QueryParser 之上构建查询，支持的语法：
+///
+/// * `预算 报告`：多个词按默认运算（AND 或 OR）组合
+/// * `预算 AND 报告`、`预算 OR 报告`、`NOT 草稿`、`-草稿`、`+预算`、`(a OR b) c`：布尔运算和分组
+/// * `"quarterly report"`：短语
+/// * `config*`：前缀，单个词时匹配以其开头的词项
+/// * `symbol:main`、`columns.金额:100`：只在指定字段中查询
+///
+/// 语法错误（如引号、括号不配对）时不报错，忽略保留字符后按普通词查询；
+/// 开启模糊匹配时，拼写错误的英文等拉丁字母单词也能命中，排在精确命中之后
+pub struct QueryBuilder<'a> {
+    index: &'a Index,
+    parser: QueryParser,
+    default_fields: Vec<Field>,
+    boosts: Vec<(Field, Score)>,
+    conjunction: bool,
+    precision: f32,
+    fuzzy_distance: u8,
+    fuzzy_fields: Vec<Field>,
+}
+
+impl<'a> QueryBuilder<'a> {
+    /// `conjunction` 为 true 时未写运算符的多个词都必须命中，否则命中其一即可
+    pub fn new(
+        index: &'a Index,
+        default_fields: Vec<Field>,
+        boosts: Vec<(Field, Score)>,
+        conjunction: bool,
+    ) -> Self {
+        let mut parser = QueryParser::for_index(index, default_fields.clone());
+        for &(field, boost) in &boosts {
+            parser.set_field_boost(field, boost);
+        }
+        if conjunction {
+            parser.set_conjunction_by_default();
+        }
+        Self {
+            index,
+            parser,
+            default_fields: default_fields.clone(),
+            boosts,
+            conjunction,
+            precision: 1.0,
+            fuzzy_distance: 0,
+            fuzzy_fields: default_fields,
+        }
+    }
+
+    /// 模糊匹配允许的最大编辑距离（0-2），为 0 时关闭模糊匹配
+    pub fn set_fuzzy_distance(&mut self, distance: u8) {
+        self.fuzzy_distance = distance.min(2);
+    }
+
+    /// 模糊匹配的字段，默认为查询的默认字段；n-gram、文件名等输出片段或前缀的字段应换成按完整单词索引的字段，
+    /// 否则片段无法模糊匹配、前缀会误匹配（如 `report` 匹配 `repository` 的前缀 `repos`）
+    pub fn set_fuzzy_fields(&mut self, fields: Vec<Field>) {
+        self.fuzzy_fields = fields;
+    }
+
+    /// 匹配精度（0-1）：未加引号的词分词为多个词项（如 n-gram）时至少命中的词项比例，为 1 时所有词项都必须命中
+    pub fn set_match_precision(&mut self, precision: f32) {
+        self.precision = precision.clamp(0.0, 1.0);
+    }
+
+    /// 按匹配精度计算 n 个词项中至少命中的个数
+    fn minimum_required(&self, n: usize) -> usize {
+        ((n as f32 * self.precision).ceil() as usize).clamp(1, n)
+    }
+
+    pub fn build(&self, text: &str) -> Box<dyn Query> {
+        query_grammar::parse_query(text)
+            .ok()
+            .and_then(|ast| self.convert(ast))
+            .unwrap_or_else(|| self.plain_terms(text))
+    }
+
+    /// 忽略保留字符和 AND/OR 运算符，将每个词作为普通词按默认运算组合；`-词` 和 `NOT 词` 仍然排除
+    fn plain_terms(&self, text: &str) -> Box<dyn Query> {
+        let plain = text.replace(SYNTAX_CHARS, " ");
+        let mut words: Vec<(Option<Occur>, UserInputAst)> = Vec::new();
+        let mut negate_next = false;
+        for word in plain.split_whitespace() {
+            if matches!(word, "AND" | "OR") {
+                continue;
+            }
+            if word == "NOT" {
+                negate_next = true;
+                continue;
+            }
+            let (negated, word) = match word.strip_prefix('-') {
+                Some(w) => (true, w.trim_start_matches('-')),
+                None => (false, word),
+            };
+            if word.is_empty() {
+                continue;
+            }
+            let literal = UserInputLiteral {
+                field_name: None,
+                phrase: word.to_string(),
+                delimiter: Delimiter::None,
+                slop: 0,
+                prefix: false,
+            };
+            let occur = (std::mem::take(&mut negate_next) || negated).then_some(Occur::MustNot);
+            words.push((occur, UserInputAst::Leaf(Box::new(UserInputLeaf::Literal(literal)))));
+        }
+        let ast = UserInputAst::Clause(words);
+        match self.convert(ast.clone()) {
+            Some(q) => q,
+            None => self.parser.build_query_from_user_input_ast_lenient(ast).0,
+        }
+    }
+
+    /// 无法构建（如字段不存在）时返回 None
+    fn convert(&self, ast: UserInputAst) -> Option<Box<dyn Query>> {
+        match ast {
+            UserInputAst::Clause(children) => {
+                let default_occur = if self.conjunction {
+                    Occur::Must
+                } else {
+                    Occur::Should
+                };
+                let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+                for (occur, child) in children {
+                    // 与 QueryParser 相同，忽略分词后没有词项的词（如停用词），避免按 AND 组合时匹配不到任何文档
+                    if self.is_empty_literal(&child) {
+                        continue;
+                    }
+                    clauses.push((occur.unwrap_or(default_occur), self.convert(child)?));
+                }
+                // 只有排除条件时，从全部文档中排除
+                if !clauses.is_empty() && clauses.iter().all(|(o, _)| *o == Occur::MustNot) {
+                    clauses.push((Occur::Must, Box::new(AllQuery)));
+                }
+                Some(Box::new(BooleanQuery::new(clauses)))
+            }
+            UserInputAst::Boost(child, boost) => Some(Box::new(BoostQuery::new(
+                self.convert(*child)?,
+                boost as Score,
+            ))),
+            UserInputAst::Leaf(mut leaf) => {
+                // 标签使用 raw 分词器按小写索引，查询词统一转为小写才能匹配；其他文本字段的分词器本身忽略大小写
+                if let UserInputLeaf::Literal(literal) = leaf.as_mut() {
+                    literal.phrase = literal.phrase.to_lowercase();
+                }
+                match *leaf {
+                    // `"a b"*` 解析为短语前缀，未加引号的 `config*` 解析为末尾带 `*` 的普通词
+                    UserInputLeaf::Literal(mut literal)
+                        if literal.prefix
+                            || (literal.delimiter == Delimiter::None
+                                && literal.phrase.len() > 1
+                                && literal.phrase.ends_with('*')) =>
+                    {
+                        literal.phrase = literal.phrase.trim_end_matches('*').to_string();
+                        literal.prefix = true;
+                        self.prefix_query(literal)
+                    }
+                    UserInputLeaf::Literal(literal) if literal.delimiter == Delimiter::None => {
+                        let exact = if self.precision < 1.0 {
+                            self.partial_query(literal.clone())?
+                        } else {
+                            let leaf = UserInputLeaf::Literal(literal.clone());
+                            self.parser
+                                .build_query_from_user_input_ast(UserInputAst::Leaf(Box::new(leaf)))
+                                .ok()?
+                        };
+                        Some(self.with_fuzzy(&literal, exact))
+                    }
+                    leaf => self
+                        .parser
+                        .build_query_from_user_input_ast(UserInputAst::Leaf(Box::new(leaf)))
+                        .ok(),
+                }
+            }
+        }
+    }
+
+    /// 前缀查询：分词后只有一个词项时匹配以该词项开头的词项，多个词项时按短语前缀匹配；
+    /// n-gram 等分词器的词项位置相同，本身已能匹配词的开头部分，按普通词查询
+    fn prefix_query(&self, literal: UserInputLiteral) -> Option<Box<dyn Query>> {
+        let schema = self.index.schema();
+        let fields = match &literal.field_name {
+            None => self.default_fields.clone(),
+            Some(name) => match schema.find_field(name) {
+                Some((field, "")) => vec![field],
+                _ => return None,
+            },
+        };
+        let mut should: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for field in fields {
+            let tokens = self.tokens(field, &literal.phrase);
+            let q: Box<dyn Query> = match tokens.as_slice() {
+                [] => continue,
+                [(_, token)] => {
+                    let q = RegexQuery::from_pattern(&format!("{}.*", regex::escape(token)), field)
+                        .ok()?;
+                    Box::new(BoostQuery::new(Box::new(q), self.boost(field)))
+                }
+                [(first, _), rest @ ..] => {
+                    let mut literal = literal.clone();
+                    literal.field_name = Some(schema.get_field_name(field).to_string());
+                    literal.prefix = rest.iter().any(|(p, _)| p != first);
+                    let leaf = UserInputAst::Leaf(Box::new(UserInputLeaf::Literal(literal)));
+                    self.parser.build_query_from_user_input_ast(leaf).ok()?
+                }
+            };
+            should.push((Occur::Should, q));
+        }
+        Some(Box::new(BooleanQuery::new(should)))
+    }
+
+    /// 按匹配精度查询未加引号的词：分词为多个词项时至少命中其中一定比例，不要求词项相邻
+    fn partial_query(&self, literal: UserInputLiteral) -> Option<Box<dyn Query>> {
+        let schema = self.index.schema();
+        let fields = match &literal.field_name {
+            None => self.default_fields.clone(),
+            Some(name) => match schema.find_field(name) {
+                Some((field, "")) => vec![field],
+                // JSON 字段（如 CSV 列）交给 QueryParser
+                _ => {
+                    let leaf = UserInputAst::Leaf(Box::new(UserInputLeaf::Literal(literal)));
+                    return self.parser.build_query_from_user_input_ast(leaf).ok();
+                }
+            },
+        };
+        let mut should: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for field in fields {
+            // n-gram 分词可能产生重复的词项
+            let mut tokens: Vec<String> = self
+                .tokens(field, &literal.phrase)
+                .into_iter()
+                .map(|(_, text)| text)
+                .collect();
+            tokens.sort();
+            tokens.dedup();
+            let q: Box<dyn Query> = match tokens.len() {
+                0 => continue,
+                1 => {
+                    let mut literal = literal.clone();
+                    literal.field_name = Some(schema.get_field_name(field).to_string());
+                    let leaf = UserInputAst::Leaf(Box::new(UserInputLeaf::Literal(literal)));
+                    self.parser.build_query_from_user_input_ast(leaf).ok()?
+                }
+                n => {
+                    let terms: Vec<(Occur, Box<dyn Query>)> = tokens
+                        .iter()
+                        .map(|text| {
+                            let term = Term::from_field_text(field, text);
+                            let q: Box<dyn Query> =
+                                Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+                            (Occur::Should, q)
+                        })
+                        .collect();
+                    let q = BooleanQuery::with_minimum_required_clauses(terms, self.minimum_required(n));
+                    Box::new(BoostQuery::new(Box::new(q), self.boost(field)))
+                }
+            };
+            should.push((Occur::Should, q));
+        }
+        Some(Box::new(BooleanQuery::new(should)))
+    }
+
+    /// 开启模糊匹配时，在精确查询之外加上较低权重的模糊查询，命中其一即可
+    fn with_fuzzy(&self, literal: &UserInputLiteral, exact: Box<dyn Query>) -> Box<dyn Query> {
+        if self.fuzzy_distance == 0 {
+            return exact;
+        }
+        let fields = match &literal.field_name {
+            None => self.fuzzy_fields.clone(),
+            Some(name) => match self.index.schema().find_field(name) {
+                Some((field, "")) if self.fuzzy_fields.contains(&field) => vec![field],
+                _ => return exact,
+            },
+        };
+        let fuzzy: Vec<(Occur, Box<dyn Query>)> = fields
+            .into_iter()
+            .filter_map(|field| {
+                let (text, distance) = self.fuzzy_term(field, &literal.phrase)?;
+                let term = Term::from_field_text(field, &text);
+                let q: Box<dyn Query> = Box::new(FuzzyTermQuery::new(term, distance, true));
+                Some((Occur::Should, q))
+            })
+            .collect();
+        if fuzzy.is_empty() {
+            return exact;
+        }
+        let fuzzy = BoostQuery::new(Box::new(BooleanQuery::new(fuzzy)), FUZZY_BOOST);
+        Box::new(BooleanQuery::new(vec![
+            (Occur::Should, exact),
+            (Occur::Should, Box::new(fuzzy)),
+        ]))
+    }
+
+    /// 模糊匹配的词项及编辑距离：只匹配按单词分词（可同时输出单词前缀）的字段中的拉丁字母单词，
+    /// n-gram 切分的词项和中文保持精确匹配；3-5 个字符的单词最多允许 1 处编辑，更短的不做模糊匹配
+    fn fuzzy_term(&self, field: Field, text: &str) -> Option<(String, u8)> {
+        let tokens = self.tokens(field, text);
+        let (_, word) = tokens.iter().max_by_key(|(_, t)| t.chars().count())?;
+        if !tokens.iter().all(|(_, t)| word.starts_with(t.as_str()))
+            || !word.chars().all(|c| c.is_alphanumeric() && !is_cjk(c))
+            || !word.chars().any(|c| c.is_alphabetic())
+        {
+            return None;
+        }
+        let distance = match word.chars().count() {
+            0..=2 => 0,
+            3..=5 => 1,
+            _ => 2,
+        }
+        .min(self.fuzzy_distance);
+        (distance > 0).then(|| (word.clone(), distance))
+    }
+
+    /// 未指定字段、且在所有默认字段中分词后都没有词项的词
+    fn is_empty_literal(&self, ast: &UserInputAst) -> bool {
+        let UserInputAst::Leaf(leaf) = ast else {
+            return false;
+        };
+        let UserInputLeaf::Literal(literal) = leaf.as_ref() else {
+            return false;
+        };
+        literal.field_name.is_none()
+            && self
+                .default_fields
+                .iter()
+                .all(|f| self.tokens(*f, &literal.phrase).is_empty())
+    }
+
+    /// 按字段的分词器切分文本，返回词项的位置和文本
+    fn tokens(&self, field: Field, text: &str) -> Vec<(usize, String)> {
+        let Ok(mut analyzer) = self.index.tokenizer_for_field(field) else {
+            return Vec::new();
+        };
+        let mut stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(token) = stream.next() {
+            tokens.push((token.position, token.text.clone()));
+        }
+        tokens
+    }
+
+    fn boost(&self, field: Field) -> Score {
+        self.boosts
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, b)| *b)
+            .unwrap_or(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::collector::{Count, TopDocs};
+    use tantivy::doc;
+    use tantivy::schema::{Schema, TextFieldIndexing, TextOptions, STRING, TEXT};
+    use tantivy::tokenizer::{LowerCaser, NgramTokenizer, TextAnalyzer};
+
+    use crate::filename_tokenizer::FilenameTokenizer;
+
+    fn count(index: &Index, field: Field, conjunction: bool, query: &str) -> usize {
+        let builder = QueryBuilder::new(index, vec![field], Vec::new(), conjunction);
+        let searcher = index.reader().unwrap().searcher();
+        searcher.search(&builder.build(query), &Count).unwrap()
+    }
+
+    #[test]
+    fn test_query_builder() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tags = schema_builder.add_text_field("tags", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        for text in ["quarterly report draft", "annual report", "configuration notes"] {
+            writer.add_document(doc!(body => text, tags => "rust")).unwrap();
+        }
+        writer.commit().unwrap();
+
+        assert_eq!(count(&index, body, true, "report draft"), 1);
+        assert_eq!(count(&index, body, false, "report draft"), 2);
+        assert_eq!(count(&index, body, true, "report OR notes"), 3);
+        assert_eq!(count(&index, body, true, "report -draft"), 1);
+        assert_eq!(count(&index, body, true, "NOT report"), 1);
+        assert_eq!(count(&index, body, true, "\"annual report\""), 1);
+        assert_eq!(count(&index, body, true, "\"report annual\""), 0);
+        assert_eq!(count(&index, body, true, "config*"), 1);
+        assert_eq!(count(&index, body, true, "rep* quart*"), 1);
+        // 引号、括号不配对时按普通词查询
+        assert_eq!(count(&index, body, true, "\"annual (report"), 1);
+        assert_eq!(count(&index, body, true, "report)"), 2);
+        // 普通词查询时仍然排除 `-词` 和 `NOT 词`，AND/OR 不作为查询词
+        assert_eq!(count(&index, body, true, "report) -draft"), 1);
+        assert_eq!(count(&index, body, true, "\"report NOT draft"), 1);
+        assert_eq!(count(&index, body, false, "(annual OR notes"), 2);
+        // 标签按小写索引，查询不区分大小写
+        assert_eq!(count(&index, body, true, "tags:Rust"), 3);
+    }
+
+    #[test]
+    fn test_match_precision() {
+        let mut schema_builder = Schema::builder();
+        let indexing = TextFieldIndexing::default()
+            .set_tokenizer("ngram2")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let body = schema_builder
+            .add_text_field("body", TextOptions::default().set_indexing_options(indexing));
+        let index = Index::create_in_ram(schema_builder.build());
+        index
+            .tokenizers()
+            .register("ngram2", NgramTokenizer::new(2, 2, false).unwrap());
+        let mut writer = index.writer(15_000_000).unwrap();
+        for text in ["abcdef", "abcxyz"] {
+            writer.add_document(doc!(body => text)).unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let count = |precision: f32, query: &str| {
+            let mut builder = QueryBuilder::new(&index, vec![body], Vec::new(), false);
+            builder.set_match_precision(precision);
+            searcher.search(&builder.build(query), &Count).unwrap()
+        };
+        // `abcdez` 切分为 ab、bc、cd、de、ez，两个文档分别命中 4 个和 2 个
+        assert_eq!(count(1.0, "abcdez"), 0);
+        assert_eq!(count(0.6, "abcdez"), 1);
+        assert_eq!(count(0.4, "abcdez"), 2);
+        assert_eq!(count(0.6, "\"abcdez\""), 0);
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut writer = index.writer(15_000_000).unwrap();
+        for text in ["quarterly report", "repart notes", "annual summary"] {
+            writer.add_document(doc!(body => text)).unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let search = |distance: u8, query: &str| {
+            let mut builder = QueryBuilder::new(&index, vec![body], Vec::new(), true);
+            builder.set_fuzzy_distance(distance);
+            searcher.search(&builder.build(query), &TopDocs::with_limit(10)).unwrap()
+        };
+        assert!(search(0, "quartely").is_empty());
+        assert_eq!(search(1, "quartely").len(), 1);
+        // 精确命中排在模糊命中之前
+        let hits = search(1, "report");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].1.doc_id, 0);
+        assert!(hits[0].0 > hits[1].0);
+        // 短词和加引号的词不做模糊匹配
+        assert!(search(2, "anual").len() == 1 && search(2, "ab").is_empty());
+        assert!(search(1, "\"quartely\"").is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_word_fields() {
+        let mut schema_builder = Schema::builder();
+        let options = |tokenizer: &str| {
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(tokenizer)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+        };
+        let title = schema_builder.add_text_field("title", options("filename"));
+        let body = schema_builder.add_text_field("body", options("cn_ngram"));
+        let words = schema_builder.add_text_field("words", options("words"));
+        let index = Index::create_in_ram(schema_builder.build());
+        let tokenizers = index.tokenizers();
+        tokenizers.register("filename", FilenameTokenizer::default());
+        tokenizers.register(
+            "cn_ngram",
+            TextAnalyzer::builder(NgramTokenizer::new(2, 3, false).unwrap())
+                .filter(LowerCaser)
+                .build(),
+        );
+        tokenizers.register(
+            "words",
+            TextAnalyzer::builder(FilenameTokenizer::words_only())
+                .filter(LowerCaser)
+                .build(),
+        );
+        let mut writer = index.writer(15_000_000).unwrap();
+        for (name, text) in [("repository.txt", "杂项"), ("notes.txt", "Quarterly Report 季度报告")] {
+            writer
+                .add_document(doc!(title => name, body => text, words => name, words => text))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let search = |fuzzy_fields: Option<Vec<Field>>, query: &str| {
+            let mut builder = QueryBuilder::new(&index, vec![title, body], Vec::new(), true);
+            builder.set_fuzzy_distance(2);
+            if let Some(fields) = fuzzy_fields {
+                builder.set_fuzzy_fields(fields);
+            }
+            let hits = searcher.search(&builder.build(query), &TopDocs::with_limit(10)).unwrap();
+            hits.into_iter().map(|(_, a)| a.doc_id).collect::<Vec<_>>()
+        };
+        // n-gram 字段无法模糊匹配，文件名字段的前缀 `repos` 会让 `report` 误匹配 repository.txt
+        assert!(search(None, "quartely").is_empty());
+        assert!(search(None, "report").contains(&0));
+        // 在完整单词字段上模糊匹配
+        assert_eq!(search(Some(vec![words]), "quartely"), vec![1]);
+        assert_eq!(search(Some(vec![words]), "report"), vec![1]);
+        assert_eq!(search(Some(vec![words]), "reprot"), vec![1]);
+        // 中文仍精确匹配
+        assert_eq!(search(Some(vec![words]), "季度"), vec![1]);
+    }
+}
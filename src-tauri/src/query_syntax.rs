@@ -0,0 +1,379 @@
+use chrono::{Local, Months, NaiveDate, TimeZone};
+
+use crate::types::{DateRangeCmd, SearchFiltersCmd, SizeRangeCmd};
+
+/// 查询操作符解析错误，携带出错的原始片段
+#[derive(Debug, PartialEq)]
+pub enum QuerySyntaxError {
+    /// 操作符后没有值，如 `type:`
+    EmptyValue(String),
+    /// 无法识别的日期，如 `modified:>2024-13-01`
+    InvalidDate(String),
+    /// 无法识别的文件大小，如 `size:<5XB`
+    InvalidSize(String),
+    /// 操作符出现在 OR 或括号中，如 `报告 OR type:pdf`，筛选条件总是与查询同时满足，无法表达
+    BooleanOperator(String),
+}
+
+/// 从查询中提取的筛选条件，以及去掉操作符后交给 QueryParser 的查询文本
+pub struct ParsedQuery {
+    pub text: String,
+    pub filters: SearchFiltersCmd,
+    /// 取反的操作符（如 `-type:pdf`、`NOT size:>5MB`），每项单独排除满足该条件的文件
+    pub excluded: Vec<SearchFiltersCmd>,
+}
+
+/// 比较表达式：`>v`、`>=v`、`<v`、`<=v`、`a..b`（任一端可省略）或单个值
+enum Comparison<'a> {
+    Gt(&'a str),
+    Ge(&'a str),
+    Lt(&'a str),
+    Le(&'a str),
+    Between(&'a str, &'a str),
+    Eq(&'a str),
+}
+
+fn comparison(value: &str) -> Comparison<'_> {
+    if let Some(v) = value.strip_prefix(">=") {
+        Comparison::Ge(v)
+    } else if let Some(v) = value.strip_prefix('>') {
+        Comparison::Gt(v)
+    } else if let Some(v) = value.strip_prefix("<=") {
+        Comparison::Le(v)
+    } else if let Some(v) = value.strip_prefix('<') {
+        Comparison::Lt(v)
+    } else if let Some((a, b)) = value.split_once("..") {
+        Comparison::Between(a, b)
+    } else {
+        Comparison::Eq(value)
+    }
+}
+
+/// 本地时间当天零点的毫秒时间戳
+fn local_millis(date: NaiveDate) -> Option<i64> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|t| t.timestamp_millis())
+}
+
+/// 日期（`2024`、`2024-06`、`2024-06-01`，也可用 `/` 分隔）覆盖的时间段 [开始, 结束)，单位毫秒
+fn date_span(s: &str) -> Option<(i64, i64)> {
+    let parts: Vec<&str> = s.split(['-', '/']).collect();
+    if parts[0].len() != 4 {
+        return None;
+    }
+    let year: i32 = parts[0].parse().ok()?;
+    let num = |i: usize| parts.get(i).and_then(|p| p.parse::<u32>().ok());
+    let (start, end) = match parts.len() {
+        1 => (
+            NaiveDate::from_ymd_opt(year, 1, 1)?,
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+        ),
+        2 => {
+            let start = NaiveDate::from_ymd_opt(year, num(1)?, 1)?;
+            (start, start.checked_add_months(Months::new(1))?)
+        }
+        3 => {
+            let day = NaiveDate::from_ymd_opt(year, num(1)?, num(2)?)?;
+            (day, day.succ_opt()?)
+        }
+        _ => return None,
+    };
+    Some((local_millis(start)?, local_millis(end)?))
+}
+
+/// 修改时间条件转为闭区间（毫秒），如 `>2024-06-01` 为 6 月 2 日零点之后
+fn date_range(value: &str) -> Option<(i64, i64)> {
+    let span = |s: &str| date_span(s.trim());
+    Some(match comparison(value) {
+        Comparison::Gt(v) => (span(v)?.1, i64::MAX),
+        Comparison::Ge(v) => (span(v)?.0, i64::MAX),
+        Comparison::Lt(v) => (i64::MIN, span(v)?.0 - 1),
+        Comparison::Le(v) => (i64::MIN, span(v)?.1 - 1),
+        Comparison::Between(a, b) => (
+            if a.is_empty() { i64::MIN } else { span(a)?.0 },
+            if b.is_empty() { i64::MAX } else { span(b)?.1 - 1 },
+        ),
+        Comparison::Eq(v) => {
+            let (start, end) = span(v)?;
+            (start, end - 1)
+        }
+    })
+}
+
+/// 文件大小，如 `500`、`100KB`、`1.5g`，单位按 1024 换算，不区分大小写
+fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim().to_lowercase();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: f64 = num.parse().ok()?;
+    let multiplier: u64 = match unit.trim() {
+        "" | "b" => 1,
+        "k" | "kb" => 1 << 10,
+        "m" | "mb" => 1 << 20,
+        "g" | "gb" => 1 << 30,
+        "t" | "tb" => 1 << 40,
+        _ => return None,
+    };
+    Some((n * multiplier as f64).round() as u64)
+}
+
+/// 文件大小条件转为闭区间（字节），不带比较符时为精确大小
+fn size_range(value: &str) -> Option<(u64, u64)> {
+    Some(match comparison(value) {
+        Comparison::Gt(v) => (parse_size(v)?.saturating_add(1), u64::MAX),
+        Comparison::Ge(v) => (parse_size(v)?, u64::MAX),
+        Comparison::Lt(v) => (0, parse_size(v)?.saturating_sub(1)),
+        Comparison::Le(v) => (0, parse_size(v)?),
+        Comparison::Between(a, b) => (
+            if a.is_empty() { 0 } else { parse_size(a)? },
+            if b.is_empty() { u64::MAX } else { parse_size(b)? },
+        ),
+        Comparison::Eq(v) => {
+            let size = parse_size(v)?;
+            (size, size)
+        }
+    })
+}
+
+/// 筛选操作符的名称
+pub const OPERATORS: [&str; 4] = ["type", "modified", "size", "path"];
+
+/// 按空白切分查询，双引号内的空白不切分
+pub fn split_tokens(query: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut quoted = false;
+    for (i, c) in query.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            if let Some(s) = start.take() {
+                tokens.push(&query[s..i]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&query[s..]);
+    }
+    tokens
+}
+
+/// 提取查询中的筛选操作符，其余部分原样保留：
+///
+/// * `type:xlsx`、`type:pdf,docx`、`type:documents`：文件类型或文件类型分组
+/// * `modified:>2024-06-01`、`modified:2024-06`、`modified:2024-01-01..2024-03-31`：修改时间（本地时间）
+/// * `size:<5MB`、`size:>=100KB`、`size:1MB..10MB`：文件大小
+/// * `path:合同`、`-path:archive`：只搜索或排除该目录下的文件，匹配方式同目录筛选
+///
+/// 同一操作符出现多次时，类型和目录满足其一即可，时间和大小取交集；前面加 `-` 或 `NOT` 时排除满足条件的文件。
+/// 操作符与查询总是同时满足，两侧的 AND 随之去掉，出现在 OR 两侧或括号中时报错
+pub fn parse_query_operators(query: &str) -> Result<ParsedQuery, QuerySyntaxError> {
+    let mut rest: Vec<&str> = Vec::new();
+    let mut file_types: Vec<String> = Vec::new();
+    let mut include_dirs: Vec<String> = Vec::new();
+    let mut exclude_dirs: Vec<String> = Vec::new();
+    let mut modified: Option<(i64, i64)> = None;
+    let mut size: Option<(u64, u64)> = None;
+    let mut excluded: Vec<SearchFiltersCmd> = Vec::new();
+    let tokens = split_tokens(query);
+    // 括号嵌套深度，引号内的括号不计
+    let mut depth = 0i32;
+    let mut skip_and = false;
+    for (i, &token) in tokens.iter().enumerate() {
+        let outer = depth;
+        if !token.starts_with('"') {
+            depth += token.matches('(').count() as i32 - token.matches(')').count() as i32;
+        }
+        if std::mem::take(&mut skip_and) && token == "AND" {
+            continue;
+        }
+        let (mut negated, body) = match token.strip_prefix('-') {
+            Some(b) => (true, b),
+            None => (false, token),
+        };
+        let Some((name, value)) = body.split_once(':') else {
+            rest.push(token);
+            continue;
+        };
+        let name = name.trim_start_matches('(').to_lowercase();
+        if !OPERATORS.contains(&name.as_str()) {
+            rest.push(token);
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| tokens[p]);
+        let next = tokens.get(i + 1).copied();
+        if outer > 0 || body.starts_with('(') || prev == Some("OR") || next == Some("OR") {
+            return Err(QuerySyntaxError::BooleanOperator(token.to_string()));
+        }
+        match prev {
+            Some("NOT") => {
+                negated = !negated;
+                rest.pop();
+            }
+            Some("AND") if rest.last() == Some(&"AND") => {
+                rest.pop();
+            }
+            _ => skip_and = true,
+        }
+        let value = value.trim_matches('"').trim();
+        if value.is_empty() {
+            return Err(QuerySyntaxError::EmptyValue(token.to_string()));
+        }
+        match name.as_str() {
+            "type" => {
+                let types: Vec<String> = value
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                if negated {
+                    excluded.push(SearchFiltersCmd {
+                        file_types: Some(types),
+                        ..Default::default()
+                    });
+                } else {
+                    file_types.extend(types);
+                }
+            }
+            "modified" => {
+                let (start, end) = date_range(value)
+                    .ok_or_else(|| QuerySyntaxError::InvalidDate(token.to_string()))?;
+                if negated {
+                    excluded.push(SearchFiltersCmd {
+                        date_range: Some(DateRangeCmd {
+                            start: Some(start),
+                            end: Some(end),
+                        }),
+                        ..Default::default()
+                    });
+                } else {
+                    modified = Some(match modified {
+                        Some((s, e)) => (s.max(start), e.min(end)),
+                        None => (start, end),
+                    });
+                }
+            }
+            "size" => {
+                let (min, max) = size_range(value)
+                    .ok_or_else(|| QuerySyntaxError::InvalidSize(token.to_string()))?;
+                if negated {
+                    excluded.push(SearchFiltersCmd {
+                        file_size_range: Some(SizeRangeCmd {
+                            min: Some(min),
+                            max: Some(max),
+                        }),
+                        ..Default::default()
+                    });
+                } else {
+                    size = Some(match size {
+                        Some((a, b)) => (a.max(min), b.min(max)),
+                        None => (min, max),
+                    });
+                }
+            }
+            _ if negated => exclude_dirs.push(value.to_string()),
+            _ => include_dirs.push(value.to_string()),
+        }
+    }
+    let non_empty = |v: Vec<String>| (!v.is_empty()).then_some(v);
+    Ok(ParsedQuery {
+        text: rest.join(" "),
+        filters: SearchFiltersCmd {
+            file_types: non_empty(file_types),
+            date_range: modified.map(|(start, end)| DateRangeCmd {
+                start: Some(start),
+                end: Some(end),
+            }),
+            include_dirs: non_empty(include_dirs),
+            exclude_dirs: non_empty(exclude_dirs),
+            file_size_range: size.map(|(min, max)| SizeRangeCmd {
+                min: Some(min),
+                max: Some(max),
+            }),
+            ..Default::default()
+        },
+        excluded,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query_operators() {
+        let parsed =
+            parse_query_operators("type:xlsx modified:>2024-06-01 size:<5MB path:合同 预算").unwrap();
+        assert_eq!(parsed.text, "预算");
+        let f = parsed.filters;
+        assert_eq!(f.file_types, Some(vec!["xlsx".to_string()]));
+        assert_eq!(f.include_dirs, Some(vec!["合同".to_string()]));
+        let june2 = date_span("2024-06-02").unwrap().0;
+        let dr = f.date_range.unwrap();
+        assert_eq!((dr.start, dr.end), (Some(june2), Some(i64::MAX)));
+        let sr = f.file_size_range.unwrap();
+        assert_eq!((sr.min, sr.max), (Some(0), Some(5 * 1024 * 1024 - 1)));
+
+        let parsed = parse_query_operators(r#"-path:"old files" type:pdf,docx "季度 报告""#).unwrap();
+        assert_eq!(parsed.text, r#""季度 报告""#);
+        assert_eq!(parsed.filters.exclude_dirs, Some(vec!["old files".to_string()]));
+        assert_eq!(parsed.filters.file_types.map(|t| t.len()), Some(2));
+        // 非操作符的字段查询保持不变
+        assert_eq!(parse_query_operators("symbol:main -type").unwrap().text, "symbol:main -type");
+    }
+
+    #[test]
+    fn test_negated_and_boolean_operators() {
+        let parsed = parse_query_operators("报告 -type:pdf NOT size:>5MB -modified:2024").unwrap();
+        assert_eq!(parsed.text, "报告");
+        assert_eq!(parsed.filters.file_types, None);
+        assert_eq!(parsed.excluded.len(), 3);
+        assert_eq!(parsed.excluded[0].file_types, Some(vec!["pdf".to_string()]));
+        let sr = parsed.excluded[1].file_size_range.as_ref().unwrap();
+        assert_eq!((sr.min, sr.max), (Some(5 * 1024 * 1024 + 1), Some(u64::MAX)));
+        assert!(parsed.excluded[2].date_range.is_some());
+
+        // 操作符两侧的 AND 随之去掉
+        let parsed = parse_query_operators("预算 AND type:xlsx AND 合同").unwrap();
+        assert_eq!(parsed.text, "预算 AND 合同");
+        assert_eq!(parse_query_operators("type:xlsx AND 预算").unwrap().text, "预算");
+        assert_eq!(parse_query_operators("预算 type:a AND type:b").unwrap().text, "预算");
+
+        for q in ["报告 OR type:pdf", "type:pdf OR 报告", "(报告 type:pdf)", "(type:pdf 报告)"] {
+            assert!(matches!(
+                parse_query_operators(q).err(),
+                Some(QuerySyntaxError::BooleanOperator(_))
+            ));
+        }
+        // 括号外的操作符不受影响
+        assert_eq!(parse_query_operators("(报告 OR 总结) type:pdf").unwrap().text, "(报告 OR 总结)");
+    }
+
+    #[test]
+    fn test_operator_values() {
+        let (start, end) = date_range("2024-02").unwrap();
+        assert_eq!(start, date_span("2024-02-01").unwrap().0);
+        assert_eq!(end, date_span("2024-03-01").unwrap().0 - 1);
+        assert_eq!(size_range("1.5k..2MB"), Some((1536, 2 * 1024 * 1024)));
+        assert_eq!(size_range(">=100kb"), Some((102400, u64::MAX)));
+        assert_eq!(
+            parse_query_operators("modified:2024-13-01").err(),
+            Some(QuerySyntaxError::InvalidDate("modified:2024-13-01".to_string()))
+        );
+        assert_eq!(
+            parse_query_operators("size:<5XB").err(),
+            Some(QuerySyntaxError::InvalidSize("size:<5XB".to_string()))
+        );
+        assert_eq!(
+            parse_query_operators("type: 报告").err(),
+            Some(QuerySyntaxError::EmptyValue("type:".to_string()))
+        );
+    }
+}
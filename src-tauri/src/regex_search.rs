@@ -0,0 +1,159 @@
+use regex::Regex;
+use tantivy::query::{Query, RegexQuery};
+use tantivy::schema::Field;
+
+/// 最多返回的命中行号和命中片段数
+const MAX_MATCHES: usize = 20;
+
+/// 可能匹配空白或位置的转义序列，如 `\s`、`\W`、`\p{..}`、`\x20`、`\b`
+const SPANNING_ESCAPES: &str = "sWDpPnrtfvxuUbBAz0123456789";
+
+/// 正则表达式的匹配是否一定不含空白（不跨越空白切分出的词项）
+///
+/// 保守判断：含 `.`、空白、`\s`、否定字符类、Unicode 类别、转义码点和位置断言的表达式都视为可能跨越词项
+fn within_token(pattern: &str) -> bool {
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(e) if !SPANNING_ESCAPES.contains(e) => {}
+                _ => return false,
+            },
+            '.' | '^' | '$' => return false,
+            '[' if chars.peek() == Some(&'^') => return false,
+            c if c.is_whitespace() => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+/// 每个匹配都必然以之开头的字面前缀，如 `user_id=\w+` 为 `user_id=`；含 `|` 或以分组、标志开头时为空
+fn literal_prefix(pattern: &str) -> String {
+    if pattern.contains('|') {
+        return String::new();
+    }
+    let mut prefix = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        let literal = match c {
+            '\\' => {
+                chars.next();
+                match chars.peek() {
+                    Some(&e) if !e.is_alphanumeric() => e,
+                    _ => break,
+                }
+            }
+            '?' | '*' | '+' | '{' | '.' | '^' | '$' | '[' | ']' | '(' | ')' | '}' => break,
+            c => c,
+        };
+        chars.next();
+        prefix.push(literal);
+    }
+    // 量词作用于前一个字符，该字符可能不出现或重复
+    if matches!(chars.peek(), Some('?' | '*' | '+' | '{')) {
+        prefix.pop();
+    }
+    prefix
+}
+
+/// 正则搜索的候选查询：匹配不跨越空白时，在按空白切分的原文词项中查找包含匹配的词项；
+/// 否则（如表达式含空白，或超出词典正则的规模限制）改为查找包含其字面前缀的词项；
+/// 都不可行时返回 `None`，不以全部文档为候选
+pub fn candidate_query(pattern: &str, tokens: Field) -> Option<Box<dyn Query>> {
+    if within_token(pattern) {
+        if let Ok(q) = RegexQuery::from_pattern(&format!(".*(?:{}).*", pattern), tokens) {
+            return Some(Box::new(q));
+        }
+    }
+    // 前缀含空白时，匹配开头所在的词项以前缀的第一个词结尾
+    let prefix = literal_prefix(pattern);
+    let token_pattern = match prefix.split_once(|c: char| c.is_ascii_whitespace()) {
+        Some((word, _)) => format!(".*{}", regex::escape(word)),
+        None => format!(".*{}.*", regex::escape(&prefix)),
+    };
+    if !prefix.is_empty() && !prefix.starts_with(|c: char| c.is_ascii_whitespace()) {
+        if let Ok(q) = RegexQuery::from_pattern(&token_pattern, tokens) {
+            return Some(Box::new(q));
+        }
+    }
+    None
+}
+
+/// 文本中各处匹配所在的行号（`line_start` 为第一行的行号），按顺序去重，最多 20 个
+pub fn matched_lines(text: &str, line_start: u64, re: &Regex) -> Vec<u64> {
+    let mut lines: Vec<u64> = Vec::new();
+    let (mut line, mut counted) = (line_start, 0);
+    for m in re.find_iter(text) {
+        line += text[counted..m.start()].matches('\n').count() as u64;
+        counted = m.start();
+        if lines.last() != Some(&line) {
+            lines.push(line);
+            if lines.len() >= MAX_MATCHES {
+                break;
+            }
+        }
+    }
+    lines
+}
+
+/// 文本中实际匹配到的片段，去重后最多 20 个，供前端高亮
+pub fn matched_terms(texts: &[&str], re: &Regex) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for m in texts.iter().flat_map(|t| re.find_iter(t)) {
+        if !m.as_str().is_empty() && !terms.iter().any(|t| t == m.as_str()) {
+            terms.push(m.as_str().to_string());
+            if terms.len() >= MAX_MATCHES {
+                break;
+            }
+        }
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within_token() {
+        assert!(within_token(r"ERR-\d{4}"));
+        assert!(within_token(r"user_id=\w+"));
+        assert!(within_token(r"(?i)timeout\S*"));
+        assert!(!within_token(r"foo.*bar"));
+        assert!(!within_token(r"foo\s+bar"));
+        assert!(!within_token(r"[^,]+"));
+        assert!(!within_token(r"^ERR"));
+    }
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(literal_prefix(r"user_id=\w+"), "user_id=");
+        assert_eq!(literal_prefix(r"ERR\-\d{4}"), "ERR-");
+        assert_eq!(literal_prefix(r"colou?r"), "colo");
+        assert_eq!(literal_prefix(r"ab+c"), "a");
+        assert_eq!(literal_prefix(r"foo|bar"), "");
+        assert_eq!(literal_prefix(r"(?i)error"), "");
+    }
+
+    #[test]
+    fn test_candidate_query() {
+        let tokens = Field::from_field_id(0);
+        assert!(candidate_query(r"ERR-\d{4}", tokens).is_some());
+        assert!(candidate_query(r"timeout after \d+s", tokens).is_some());
+        assert!(candidate_query(r".*\d{4}", tokens).is_none());
+        assert!(candidate_query(r"^\s+$", tokens).is_none());
+    }
+
+    #[test]
+    fn test_matches() {
+        let re = Regex::new(r"ERR-\d{4}").unwrap();
+        let text = "ok\nERR-1234 failed\nok\nERR-5678 ERR-1234\n";
+        assert_eq!(matched_lines(text, 1, &re), vec![2, 4]);
+        assert_eq!(matched_lines(text, 101, &re), vec![102, 104]);
+        assert_eq!(
+            matched_terms(&["log ERR-0001", text], &re),
+            vec!["ERR-0001", "ERR-1234", "ERR-5678"]
+        );
+    }
+}
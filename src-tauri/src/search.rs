@@ -1,14 +1,39 @@
+use std::collections::{HashMap, HashSet};
 use std::ops::Bound;
+use std::sync::Arc;
 
+use crate::collector::{rank_cmp, DocFilter, FacetFilters, FileFacets, FileGroupCollector, FileHit};
+use crate::file_types::{expand_file_types, file_type_groups};
+use crate::index_store::live_index_dir;
 use crate::indexer;
-use crate::types::{SearchFiltersCmd, SearchResultPayload, SearchResponsePayload};
-use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, RangeQuery};
-use tantivy::schema::{IndexRecordOption, Value};
+use crate::markdown;
+use crate::media_meta::{is_audio_ext, is_video_ext};
+use crate::pinyin_tokenizer::{is_pinyin_query, pinyin_matches};
+use crate::query_builder::QueryBuilder;
+use crate::query_syntax::{
+    parse_query_operators, split_tokens, ParsedQuery, QuerySyntaxError, OPERATORS,
+};
+use crate::regex_search::{candidate_query, matched_lines, matched_terms};
+use crate::settings::{load_settings, DefaultOperator, SearchSettings};
+use crate::types::{
+    FacetCountPayload, ImageMetaPayload, MediaMetaPayload, PassagePayload, SearchErrorPayload,
+    SearchFacetsPayload, SearchFiltersCmd, SearchResultPayload, SearchResponsePayload, SearchSort,
+};
+use crate::unicode_norm::{dir_key, is_absolute_dir_key, nfc};
+use crate::zh_normalize::normalized_matches;
+use chrono::Datelike;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, RangeQuery, RegexQuery, TermQuery};
+use tantivy::schema::{Field, IndexRecordOption, Schema, Value};
+use tantivy::tokenizer::TokenStream;
 use tantivy::Term;
-use tantivy::{collector::TopDocs, query::QueryParser, TantivyDocument};
-use tantivy::collector::Count;
+use regex::Regex;
+use rust_i18n::t;
+use tantivy::{Searcher, TantivyDocument};
 use tauri::AppHandle;
 
+/// 一次搜索最多归组的文件数，超出时只保留排名最前的文件，分页以此为上限，命中总数不受影响
+const MAX_FILE_HITS: usize = 10_000;
+
 fn byte_to_char_idx(s: &str, byte_idx: usize) -> usize {
     s[..byte_idx].chars().count()
 }
@@ -23,6 +48,187 @@ fn char_to_byte_idx(s: &str, char_idx: usize) -> usize {
     s.len()
 }
 
+/// 查询在原始文本中首次出现的字节位置（忽略大小写）
+fn first_match_byte(text: &str, query: &str) -> Option<usize> {
+    if text.is_empty() || query.is_empty() {
+        return None;
+    }
+    let t_low = text.to_lowercase();
+    let pos_b = t_low.find(&query.to_lowercase())?;
+    Some(char_to_byte_idx(text, byte_to_char_idx(&t_low, pos_b)))
+}
+
+/// 去掉字段前缀、引号、括号和前缀符号后的查询词（小写），不含运算符和排除的词
+fn plain_query_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .filter(|t| !t.starts_with('-') && !matches!(*t, "AND" | "OR" | "NOT"))
+        .map(|t| {
+            let t = t.rsplit(':').next().unwrap_or(t);
+            t.trim_matches(['"', '(', ')', '+', '*']).to_lowercase()
+        })
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// 将 `表头:值` 形式的查询改写为 CSV 列字段查询 `columns.表头:值`
+///
+/// 只改写已索引的 CSV 表头（见 `is_csv_column`），引号内的文本、已有字段名、筛选操作符（含 `-type:pdf` 等否定形式）
+/// 以及 `10:30`、`http://…` 等普通文本保持不变
+fn rewrite_column_queries(
+    query: &str,
+    schema: &Schema,
+    is_column: impl Fn(&str) -> bool,
+) -> String {
+    split_tokens(query)
+        .into_iter()
+        .map(|tok| {
+            let (prefix, rest) = tok.split_at(tok.len() - tok.trim_start_matches(['+', '-', '(']).len());
+            match rest.split_once(':') {
+                Some((name, value))
+                    if !name.is_empty()
+                        && !value.is_empty()
+                        && !value.starts_with('/')
+                        && !name.contains('"')
+                        && !OPERATORS.contains(&name.to_lowercase().as_str())
+                        && schema.find_field(name).is_none()
+                        && is_column(&name.to_lowercase()) =>
+                {
+                    // 表头中的 `.` 须转义，否则会被当作 JSON 路径的分隔符
+                    format!(
+                        "{}columns.{}:{}",
+                        prefix,
+                        name.to_lowercase().replace('.', "\\."),
+                        value
+                    )
+                }
+                _ => tok.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 索引中是否有该 CSV 表头（小写）：在各段的词典中查找以该列路径开头的词项
+fn is_csv_column(searcher: &Searcher, columns: Field, name: &str) -> bool {
+    let term = Term::from_field_json_path(columns, &name.replace('.', "\\."), false);
+    // 列路径以结束符 0 结尾，该列的词项都在 [路径, 路径末字节加 1) 区间内
+    let start = term.serialized_value_bytes();
+    let mut end = start.to_vec();
+    if let Some(last) = end.last_mut() {
+        *last += 1;
+    }
+    searcher.segment_readers().iter().any(|segment| {
+        let Ok(inverted) = segment.inverted_index(columns) else {
+            return false;
+        };
+        let stream = inverted.terms().range().ge(start).lt(&end).into_stream();
+        stream.is_ok_and(|mut stream| stream.advance())
+    })
+}
+
+/// 查找与查询词匹配的代码符号，返回其定义行号（完全匹配优先于包含匹配）
+fn matched_symbol_line(
+    doc: &TantivyDocument,
+    symbol: Field,
+    symbol_line: Field,
+    query: &str,
+) -> Option<u64> {
+    let terms = plain_query_terms(query);
+    if terms.is_empty() {
+        return None;
+    }
+    let symbols: Vec<(String, u64)> = doc
+        .get_all(symbol)
+        .zip(doc.get_all(symbol_line))
+        .filter_map(|(s, l)| Some((s.as_str()?.to_lowercase(), l.as_u64()?)))
+        .collect();
+    symbols
+        .iter()
+        .find(|(s, _)| terms.iter().any(|t| s == t))
+        .or_else(|| symbols.iter().find(|(s, _)| terms.iter().any(|t| s.contains(t.as_str()))))
+        .map(|(_, l)| *l)
+}
+
+/// 按行分块的文档中包含查询词的行号（最多 20 个）
+fn matched_chunk_lines(content: &str, line_start: u64, query: &str) -> Vec<u64> {
+    let terms = plain_query_terms(query);
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| {
+            let l = l.to_lowercase();
+            terms.iter().any(|t| l.contains(t.as_str()))
+        })
+        .map(|(i, _)| line_start + i as u64)
+        .take(20)
+        .collect()
+}
+
+/// 查找 CSV 内容中首个命中的数据行，返回其在源文件中的行号
+///
+/// `columns.表头:值` 须命中该列的单元格，没有列查询时任一单元格包含查询词即可
+fn matched_csv_row(content: &str, query: &str) -> Option<u64> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .skip_while(|(_, l)| l.trim().is_empty());
+    let (_, header) = lines.next()?;
+    let headers: Vec<String> = header.split('\t').map(|h| h.to_lowercase()).collect();
+    let columns: Vec<(usize, String)> = split_tokens(query)
+        .into_iter()
+        .filter(|t| !t.starts_with('-'))
+        .filter_map(|t| {
+            let (name, value) = t
+                .trim_start_matches(['+', '('])
+                .strip_prefix("columns.")?
+                .split_once(':')?;
+            let col = headers.iter().position(|h| h == name)?;
+            Some((col, value.trim_matches(['"', '(', ')', '*']).to_lowercase()))
+        })
+        .collect();
+    let terms = plain_query_terms(query);
+    lines
+        .find(|(_, l)| {
+            let l = l.to_lowercase();
+            if columns.is_empty() {
+                return terms.iter().any(|t| l.contains(t.as_str()));
+            }
+            let cells: Vec<&str> = l.split('\t').collect();
+            columns
+                .iter()
+                .all(|(col, v)| cells.get(*col).is_some_and(|c| c.contains(v.as_str())))
+        })
+        .map(|(i, _)| i as u64 + 1)
+}
+
+/// 索引中该字段是否有词项
+fn has_terms(searcher: &Searcher, field: Field) -> bool {
+    searcher.segment_readers().iter().any(|segment| {
+        segment
+            .inverted_index(field)
+            .is_ok_and(|inverted| inverted.terms().num_terms() > 0)
+    })
+}
+
+/// 目录筛选条件：绝对路径匹配该目录及其子目录下的文件，相对路径（如 `archive/`）匹配任意位置的同名目录
+fn dir_query(dirs: Field, dir: &str) -> Result<Option<Box<dyn Query>>, String> {
+    let key = dir_key(dir);
+    if key.is_empty() {
+        return Ok(None);
+    }
+    if is_absolute_dir_key(&key) {
+        return Ok(Some(Box::new(TermQuery::new(
+            Term::from_field_text(dirs, &key),
+            IndexRecordOption::Basic,
+        ))));
+    }
+    let relative = key.trim_start_matches("./").trim_start_matches('/');
+    let q = RegexQuery::from_pattern(&format!("(.*/)?{}", regex::escape(relative)), dirs)
+        .map_err(|e| format!("parse query error: {}", e))?;
+    Ok(Some(Box::new(q)))
+}
+
 /// 生成包含查询高亮的文本片段
 ///
 /// # 参数
@@ -46,145 +252,621 @@ fn snippet_with_highlight(text: &str, query: &str, pre_chars: usize, post_chars:
     if let Some(pos_b) = t_low.find(&q_low) {
         let pos_c = byte_to_char_idx(text, pos_b);
         let q_len_c = query.chars().count();
-        let total_c = text.chars().count();
-        let start_c = pos_c.saturating_sub(pre_chars);
-        let end_c = usize::min(total_c, pos_c + q_len_c + post_chars);
-        let start_b = char_to_byte_idx(text, start_c);
         let match_start_b = char_to_byte_idx(text, pos_c);
         let match_end_b = char_to_byte_idx(text, pos_c + q_len_c);
-        let end_b = char_to_byte_idx(text, end_c);
-        let mut out = String::new();
-        out.push_str(&text[start_b..match_start_b]);
-        out.push_str("<mark>");
-        out.push_str(&text[match_start_b..match_end_b]);
-        out.push_str("</mark>");
-        out.push_str(&text[match_end_b..end_b]);
-        return out;
+        return snippet_around(text, match_start_b, match_end_b, pre_chars, post_chars);
     }
     let take_c = usize::min(text.chars().count(), pre_chars + post_chars);
     let end_b = char_to_byte_idx(text, take_c);
     text[..end_b].to_string()
 }
 
+/// 以正则表达式的首个匹配生成高亮片段，没有匹配时返回空字符串
+fn snippet_with_regex(text: &str, re: &Regex, pre_chars: usize, post_chars: usize) -> String {
+    match re.find(text) {
+        Some(m) if !m.is_empty() => snippet_around(text, m.start(), m.end(), pre_chars, post_chars),
+        _ => String::new(),
+    }
+}
+
+/// 高亮 `[match_start_b, match_end_b)`（字节位置）并保留前后若干字符的文本片段
+fn snippet_around(
+    text: &str,
+    match_start_b: usize,
+    match_end_b: usize,
+    pre_chars: usize,
+    post_chars: usize,
+) -> String {
+    let pos_c = byte_to_char_idx(text, match_start_b);
+    let end_match_c = byte_to_char_idx(text, match_end_b);
+    let total_c = text.chars().count();
+    let start_b = char_to_byte_idx(text, pos_c.saturating_sub(pre_chars));
+    let end_b = char_to_byte_idx(text, usize::min(total_c, end_match_c + post_chars));
+    let mut out = String::new();
+    out.push_str(&text[start_b..match_start_b]);
+    out.push_str("<mark>");
+    out.push_str(&text[match_start_b..match_end_b]);
+    out.push_str("</mark>");
+    out.push_str(&text[match_end_b..end_b]);
+    out
+}
+
+/// 按排序方式排列文件级命中，排序字段和相关度都相同时按路径排序
+fn sort_hits(hits: &mut [(String, FileHit)], sort: SearchSort) {
+    hits.sort_by(|(pa, a), (pb, b)| rank_cmp(a, b, sort).then_with(|| pa.cmp(pb)));
+}
+
+/// 统计命中文件在各文件类型、索引目录和修改时间中的数量，每个分面只统计满足另外两项分面筛选条件的文件
+///
+/// 修改时间按本地日历分为互不包含的 today、week、month、older：今天零点起、本周一零点起（今天之前）、
+/// 本月一日零点起（本周之前）修改的文件，其余（包括没有修改时间的）为 older；本周跨月时，上月的那几天计入 week
+fn collect_facets<'a>(files: impl Iterator<Item = &'a FileFacets>) -> SearchFacetsPayload {
+    let today = chrono::Local::now().date_naive();
+    let start_ms = |day: chrono::NaiveDate| {
+        day.and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+            .map(|t| t.timestamp_millis())
+            .unwrap_or(i64::MIN)
+    };
+    let periods = [
+        ("today", start_ms(today)),
+        (
+            "week",
+            start_ms(today - chrono::Days::new(u64::from(today.weekday().num_days_from_monday()))),
+        ),
+        ("month", start_ms(today.with_day(1).unwrap_or(today))),
+    ];
+    let mut file_types: HashMap<String, usize> = HashMap::new();
+    let mut directories: HashMap<String, usize> = HashMap::new();
+    let mut dates: HashMap<String, usize> = HashMap::new();
+    for f in files {
+        if f.dir_ok && f.date_ok {
+            if let Some(t) = &f.file_type {
+                *file_types.entry(t.clone()).or_default() += 1;
+            }
+        }
+        if f.type_ok && f.date_ok {
+            if let Some(d) = &f.root_dir {
+                *directories.entry(d.clone()).or_default() += 1;
+            }
+        }
+        if f.type_ok && f.dir_ok {
+            let bucket = f
+                .modified_time
+                .and_then(|mt| periods.iter().find(|(_, start)| mt >= *start))
+                .map_or("older", |(bucket, _)| *bucket);
+            *dates.entry(bucket.to_string()).or_default() += 1;
+        }
+    }
+    let sorted = |counts: HashMap<String, usize>| {
+        let mut v: Vec<FacetCountPayload> = counts
+            .into_iter()
+            .map(|(value, count)| FacetCountPayload { value, count })
+            .collect();
+        v.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        v
+    };
+    SearchFacetsPayload {
+        file_types: sorted(file_types),
+        directories: sorted(directories),
+        dates: sorted(dates),
+    }
+}
+
+/// 目录筛选在收集器中的匹配规则，与 `dir_query` 相同：绝对路径匹配该目录，相对路径匹配任意位置的同名目录
+fn dir_regex(dir: &str) -> Result<Option<Regex>, String> {
+    let key = dir_key(dir);
+    if key.is_empty() {
+        return Ok(None);
+    }
+    let pattern = if is_absolute_dir_key(&key) {
+        format!("^{}$", regex::escape(&key))
+    } else {
+        let relative = key.trim_start_matches("./").trim_start_matches('/');
+        format!("^(.*/)?{}$", regex::escape(relative))
+    };
+    Regex::new(&pattern)
+        .map(Some)
+        .map_err(|e| format!("parse query error: {}", e))
+}
+
+/// 统计分面时，从筛选面板中取出文件类型、目录和修改时间条件，改为在收集器中判断
+fn take_facet_filters(
+    f: &mut SearchFiltersCmd,
+    settings: &SearchSettings,
+) -> Result<FacetFilters, String> {
+    let file_types = f.file_types.take().and_then(|types| {
+        let groups = file_type_groups(&settings.file_type_groups);
+        let expanded: HashSet<String> = expand_file_types(&types, &groups).into_iter().collect();
+        (!expanded.is_empty()).then_some(expanded)
+    });
+    let mut dirs = Vec::new();
+    for dir in f.include_dirs.take().unwrap_or_default() {
+        dirs.extend(dir_regex(&dir)?);
+    }
+    let modified = f
+        .date_range
+        .take()
+        .map(|dr| (dr.start.unwrap_or(i64::MIN), dr.end.unwrap_or(i64::MAX)));
+    Ok(FacetFilters {
+        file_types,
+        dirs: (!dirs.is_empty()).then_some(dirs),
+        modified,
+    })
+}
+
+/// 与查询条件组合的子句列表
+type Clauses = Vec<(Occur, Box<dyn Query>)>;
+
+/// 查询操作符解析错误转为本地化的错误信息
+fn syntax_error(e: QuerySyntaxError) -> SearchErrorPayload {
+    let (code, key, token) = match e {
+        QuerySyntaxError::EmptyValue(t) => ("emptyValue", "search.errors.empty_value", t),
+        QuerySyntaxError::InvalidDate(t) => ("invalidDate", "search.errors.invalid_date", t),
+        QuerySyntaxError::InvalidSize(t) => ("invalidSize", "search.errors.invalid_size", t),
+        QuerySyntaxError::BooleanOperator(t) => {
+            ("booleanOperator", "search.errors.boolean_operator", t)
+        }
+    };
+    SearchErrorPayload {
+        code: code.to_string(),
+        message: t!(key, token = token).to_string(),
+        token: Some(token),
+    }
+}
+
+/// 正则搜索无法进行时的本地化错误信息：表达式无效、索引中没有原文词项，或表达式无法筛选候选文档
+fn regex_error(code: &str, key: &str, pattern: &str) -> SearchErrorPayload {
+    SearchErrorPayload {
+        code: code.to_string(),
+        message: t!(key, token = pattern).to_string(),
+        token: Some(pattern.to_string()),
+    }
+}
+
+/// 正则搜索时用存储的标题和内容校验候选文档，只保留确实匹配的父文档和段落
+fn regex_filter(fields: &indexer::IndexFields, re: &Regex) -> DocFilter {
+    let (fields, re) = (*fields, re.clone());
+    Arc::new(move |doc: &TantivyDocument| {
+        doc.get_all(fields.title)
+            .chain(doc.get_all(fields.meta_title))
+            .chain(doc.get_all(fields.content))
+            .filter_map(|v| v.as_str())
+            .any(|s| re.is_match(s))
+    })
+}
+
+/// 筛选条件对应的查询子句，筛选面板和查询中的操作符分别生成，两者同时满足
+fn filter_clauses(
+    f: SearchFiltersCmd,
+    index: &tantivy::Index,
+    fields: &indexer::IndexFields,
+    settings: &SearchSettings,
+) -> Result<Clauses, String> {
+    let mut clauses: Clauses = Vec::new();
+    // 匹配文件类型，文件类型分组展开为其中的扩展名
+    if let Some(file_types) = f.file_types {
+        if !file_types.is_empty() {
+            let groups = file_type_groups(&settings.file_type_groups);
+            let expanded = expand_file_types(&file_types, &groups);
+            let mut should_terms: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+            for ft in expanded {
+                let term = Term::from_field_text(fields.file_type, &ft);
+                should_terms.push((
+                    Occur::Should,
+                    Box::new(tantivy::query::TermQuery::new(
+                        term,
+                        IndexRecordOption::Basic,
+                    )),
+                ));
+            }
+            if !should_terms.is_empty() {
+                clauses.push((Occur::Must, Box::new(BooleanQuery::new(should_terms))));
+            }
+        }
+    }
+    // 匹配时间区间
+    if let Some(dr) = f.date_range {
+        let start = dr.start.unwrap_or(i64::MIN);
+        let end = dr.end.unwrap_or(i64::MAX);
+        let rq = RangeQuery::new(
+            Bound::Included(Term::from_field_i64(fields.modified_time, start)),
+            Bound::Included(Term::from_field_i64(fields.modified_time, end)),
+        );
+        clauses.push((Occur::Must, Box::new(rq)));
+    }
+    // 匹配文件大小区间
+    if let Some(sr) = f.file_size_range {
+        let rq = RangeQuery::new(
+            Bound::Included(Term::from_field_u64(fields.file_size, sr.min.unwrap_or(0))),
+            Bound::Included(Term::from_field_u64(fields.file_size, sr.max.unwrap_or(u64::MAX))),
+        );
+        clauses.push((Occur::Must, Box::new(rq)));
+    }
+    // 匹配图片拍摄时间区间
+    if let Some(tr) = f.taken_range {
+        let rq = RangeQuery::new(
+            Bound::Included(Term::from_field_i64(fields.taken_at, tr.start.unwrap_or(i64::MIN))),
+            Bound::Included(Term::from_field_i64(fields.taken_at, tr.end.unwrap_or(i64::MAX))),
+        );
+        clauses.push((Occur::Must, Box::new(rq)));
+    }
+    // 匹配相机品牌或型号，按相机字段的分词器切分后每个词都必须命中
+    if let Some(camera) = f.camera.filter(|c| !c.trim().is_empty()) {
+        let mut analyzer = index
+            .tokenizer_for_field(fields.camera)
+            .map_err(|e| format!("tokenizer error: {}", e))?;
+        let mut stream = analyzer.token_stream(&camera);
+        let mut camera_terms: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        while let Some(token) = stream.next() {
+            camera_terms.push((
+                Occur::Must,
+                Box::new(tantivy::query::TermQuery::new(
+                    Term::from_field_text(fields.camera, &token.text),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+        if !camera_terms.is_empty() {
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(camera_terms))));
+        }
+    }
+    // 只保留指定目录下的文件
+    let mut include_dirs: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+    for dir in f.include_dirs.unwrap_or_default() {
+        if let Some(q) = dir_query(fields.dirs, &dir)? {
+            include_dirs.push((Occur::Should, q));
+        }
+    }
+    if !include_dirs.is_empty() {
+        clauses.push((Occur::Must, Box::new(BooleanQuery::new(include_dirs))));
+    }
+    // 排除指定目录下的文件
+    for dir in f.exclude_dirs.unwrap_or_default() {
+        if let Some(q) = dir_query(fields.dirs, &dir)? {
+            clauses.push((Occur::MustNot, q));
+        }
+    }
+    Ok(clauses)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn do_search_index(
     app: AppHandle,
     query: String,
     limit: Option<usize>,
     offset: Option<usize>,
     filters: Option<SearchFiltersCmd>,
-) -> Result<SearchResponsePayload, String> {
+    sort: SearchSort,
+    facets: bool,
+    match_precision: f32,
+    fuzzy_distance: u8,
+    regex: bool,
+) -> Result<SearchResponsePayload, SearchErrorPayload> {
     let limit = limit.unwrap_or(20);
     let offset = offset.unwrap_or(0);
-    let index_dir = indexer::app_index_dir(&app);
+    // 与索引时一致，查询统一为 NFC；提取 `type:`、`modified:`、`size:`、`path:` 等操作符作为筛选条件，其余部分交给 QueryParser
+    // 正则模式不提取操作符，查询原样（包括空白）作为正则表达式，筛选条件只来自筛选面板
+    let parsed = if regex {
+        ParsedQuery {
+            text: nfc(&query),
+            filters: SearchFiltersCmd::default(),
+            excluded: Vec::new(),
+        }
+    } else {
+        parse_query_operators(&nfc(&query)).map_err(syntax_error)?
+    };
+    let query = parsed.text;
+    // 正则模式：查询整体作为正则表达式（区分大小写，可用 `(?i)` 忽略大小写）
+    let regex = if regex && !query.trim().is_empty() {
+        Some(
+            Regex::new(&query)
+                .map_err(|_| regex_error("invalidRegex", "search.errors.invalid_regex", &query))?,
+        )
+    } else {
+        None
+    };
+    let settings = load_settings(&app);
+    let index_dir = live_index_dir(&indexer::app_index_dir(&app));
     if !index_dir.exists() {
-        return Ok(SearchResponsePayload { results: vec![], total_count: 0});
+        return Ok(SearchResponsePayload {
+            results: vec![],
+            total_count: 0,
+            truncated: false,
+            facets: None,
+        });
     }
 
     let index =
         tantivy::Index::open_in_dir(&index_dir).map_err(|e| format!("open index error: {}", e))?;
-    indexer::register_tokenizers_for(&index); // 注册索引器中定义的分词器
+    indexer::register_tokenizers_for(&index, &settings.indexing); // 注册索引器中定义的分词器
 
     let schema = index.schema();
-    let (title, content, file_path, file_type, modified_time, _) = indexer::index_fields(&schema);
+    let fields = indexer::index_fields(&schema)?;
+    let (title, content, file_type, modified_time) = (
+        fields.title,
+        fields.content,
+        fields.file_type,
+        fields.modified_time,
+    );
 
     let reader = index.reader().map_err(|e| format!("reader error: {}", e))?;
     let searcher = reader.searcher();
-    // 根据标题和内容字段进行查询，并应用后端过滤
-    let parser = QueryParser::for_index(&index, vec![title, content]);
-    let base_query: Box<dyn Query> = if query.trim().is_empty() {
+    // 根据文件名、front matter 与音视频标签中的标题、内容、Markdown 标题、标签、代码符号、相机、音视频艺术家、专辑和所在目录字段进行查询，标题和符号命中优先于正文命中，目录命中最低
+    // 可以使用 `symbol:名称` 只在代码符号中查询，`artist:名称`、`album:名称` 只在音视频标签中查询
+    // 支持 AND/OR/NOT、`-排除`、`"短语"`、`前缀*` 等语法，语法错误时按普通词查询，见 QueryBuilder
+    let mut builder = QueryBuilder::new(
+        &index,
+        vec![
+            title,
+            fields.meta_title,
+            content,
+            fields.headings,
+            fields.tags,
+            fields.symbol,
+            fields.camera,
+            fields.artist,
+            fields.album,
+            fields.path,
+        ],
+        vec![(fields.headings, 2.0), (fields.symbol, 3.0), (fields.path, 0.5)],
+        settings.search.default_operator == DefaultOperator::And,
+    );
+    // 匹配精度低于 1 时，n-gram 等切分出的词项只需命中一定比例
+    builder.set_match_precision(match_precision);
+    // 模糊匹配：拉丁字母单词允许少量拼写错误，中文 n-gram 词项仍精确匹配，模糊命中排在精确命中之后
+    builder.set_fuzzy_distance(fuzzy_distance);
+    // 在标题（设置中开启后还有内容）的完整单词上模糊匹配：n-gram 片段无法模糊匹配，文件名字段的单词前缀会误匹配
+    builder.set_fuzzy_fields(vec![fields.words, fields.symbol, fields.camera]);
+    // `表头:值` 改写为 CSV 列查询，正则模式不改写
+    let column_query = if regex.is_some() {
+        String::new()
+    } else {
+        rewrite_column_queries(&query, &schema, |name| {
+            is_csv_column(&searcher, fields.columns, name)
+        })
+    };
+    let query_terms = if regex.is_some() {
+        Vec::new()
+    } else {
+        plain_query_terms(&query)
+    };
+    // 开启拼音搜索且索引中已有拼音词项时（切换设置后尚未重建索引的不算），可能是拼音的查询词同时按前缀匹配标题（及内容）的拼音
+    let mut pinyin_fields: Vec<Field> = Vec::new();
+    if settings.indexing.pinyin {
+        pinyin_fields.push(fields.title_pinyin);
+        if settings.indexing.pinyin_content {
+            pinyin_fields.push(fields.content_pinyin);
+        }
+    }
+    pinyin_fields.retain(|f| has_terms(&searcher, *f));
+    let pinyin_terms: Vec<String> = if !pinyin_fields.is_empty() {
+        query_terms
+            .iter()
+            .filter(|t| is_pinyin_query(t))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut base_query: Box<dyn Query> = if query.trim().is_empty() {
         Box::new(AllQuery)
+    } else if regex.is_some() {
+        // 正则搜索先在原文词项中筛选候选文档，再用存储的内容逐个校验；无法筛选时不逐个扫描全部文档，提示错误
+        if !settings.indexing.regex_index || !has_terms(&searcher, fields.content_tokens) {
+            return Err(regex_error("regexNoIndex", "search.errors.regex_no_index", &query));
+        }
+        candidate_query(&query, fields.content_tokens)
+            .ok_or_else(|| regex_error("regexTooBroad", "search.errors.regex_too_broad", &query))?
     } else {
-        Box::new(
-            parser
-                .parse_query(&query)
-                .map_err(|e| format!("parse query error: {}", e))?,
-        )
+        builder.build(&column_query)
     };
-    let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, base_query)];
-    if let Some(f) = filters {
-        // 匹配文件类型
-        if let Some(file_types) = f.file_types {
-            if !file_types.is_empty() {
-                let mut expanded: Vec<String> = Vec::new();
-                for ft in file_types {
-                    if ft == "plain" {
-                        expanded.extend(["js", "ts", "json", "rs"].iter().map(|s| s.to_string()));
-                    } else {
-                        expanded.push(ft);
-                    }
-                }
-                expanded.sort();
-                expanded.dedup();
-                let mut should_terms: Vec<(Occur, Box<dyn Query>)> = Vec::new();
-                for ft in expanded {
-                    let term = Term::from_field_text(file_type, &ft);
-                    should_terms.push((
-                        Occur::Should,
-                        Box::new(tantivy::query::TermQuery::new(
-                            term,
-                            IndexRecordOption::Basic,
-                        )),
-                    ));
-                }
-                if !should_terms.is_empty() {
-                    clauses.push((Occur::Must, Box::new(BooleanQuery::new(should_terms))));
-                }
+    if !pinyin_terms.is_empty() {
+        let mut should: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Should, base_query)];
+        for term in &pinyin_terms {
+            for field in &pinyin_fields {
+                let q = RegexQuery::from_pattern(&format!("{}.*", regex::escape(term)), *field)
+                    .map_err(|e| format!("parse query error: {}", e))?;
+                should.push((Occur::Should, Box::new(q)));
             }
         }
-        // 匹配时间区间
-        if let Some(dr) = f.date_range {
-            let start = dr.start.unwrap_or(i64::MIN);
-            let end = dr.end.unwrap_or(i64::MAX);
-            let rq = RangeQuery::new(
-                Bound::Included(Term::from_field_i64(modified_time, start)),
-                Bound::Included(Term::from_field_i64(modified_time, end)),
-            );
-            clauses.push((Occur::Must, Box::new(rq)));
-        }
+        base_query = Box::new(BooleanQuery::new(should));
+    }
+    // 统计分面时，筛选面板中的文件类型、目录和修改时间条件在收集器中判断，同一次搜索得到每个分面不含自身条件的计数
+    let mut panel = filters.unwrap_or_default();
+    let facet_filters = if facets {
+        Some(take_facet_filters(&mut panel, &settings.search)?)
+    } else {
+        None
+    };
+    let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, base_query)];
+    clauses.extend(filter_clauses(panel, &index, &fields, &settings.search)?);
+    clauses.extend(filter_clauses(parsed.filters, &index, &fields, &settings.search)?);
+    for f in parsed.excluded {
+        let excluded = filter_clauses(f, &index, &fields, &settings.search)?;
+        clauses.push((Occur::MustNot, Box::new(BooleanQuery::new(excluded))));
     }
-
     // 将所有查询条件拼接到一起，成为最终条件
     let final_query = BooleanQuery::new(clauses);
-    let (top_docs, total_count) = searcher
-        .search(&final_query, &(TopDocs::with_limit(limit).and_offset(offset), Count))
+    // 按文件归组：长文档的多个段落合并为一个结果，按父文档与最佳段落得分（或指定的排序方式）排名
+    let collector = FileGroupCollector::with_limit(MAX_FILE_HITS)
+        .with_sort(sort)
+        .with_facets(facet_filters)
+        .with_filter(regex.as_ref().map(|re| regex_filter(&fields, re)));
+    let groups = searcher
+        .search(&final_query, &collector)
         .map_err(|e| format!("search error: {}", e))?;
-    if top_docs.is_empty() {
-        return Ok(SearchResponsePayload { results: vec![], total_count });
-    }
-    let max_score = top_docs.first().map(|(s, _)| *s).unwrap_or(1.0);
+    let total_count = groups.total();
+    // 分面统计基于全部命中文件，不受分页和归组上限影响
+    let facets = facets.then(|| collect_facets(groups.matched.values()));
+    let mut hits: Vec<(String, FileHit)> = groups.hits.into_iter().collect();
+    // 命中文件超过上限时只能分页浏览排名最前的文件
+    let truncated = total_count > hits.len();
+    sort_hits(&mut hits, sort);
+    let max_score = hits.iter().map(|(_, h)| h.score()).fold(0.0, f32::max);
 
     let mut results = Vec::new();
-    for (score, addr) in top_docs {
-        let retrieved: TantivyDocument = searcher
-            .doc(addr)
-            .map_err(|e| format!("doc read error: {}", e))?;
+    for (path_val, hit) in hits.into_iter().skip(offset).take(limit) {
+        let score = hit.score();
+        // 父文档提供标题和元数据，段落子文档提供内容；未拆分的文档两者相同
+        let read_doc = |addr: Option<(f32, tantivy::DocAddress)>| -> Result<Option<TantivyDocument>, String> {
+            match addr {
+                Some((_, a)) => searcher
+                    .doc(a)
+                    .map(Some)
+                    .map_err(|e| format!("doc read error: {}", e)),
+                None => Ok(None),
+            }
+        };
+        let parent_doc = read_doc(hit.doc)?;
+        let passage_doc = read_doc(hit.passage)?;
+        let Some(retrieved) = parent_doc.as_ref().or(passage_doc.as_ref()) else {
+            continue;
+        };
+        let content_doc = passage_doc.as_ref().unwrap_or(retrieved);
         let title_val = retrieved
             .get_first(title)
             .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let content_val = retrieved
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| {
+                std::path::Path::new(&path_val)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+        let content_val = content_doc
             .get_first(content)
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string();
-        let path_val = retrieved
-            .get_first(file_path)
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
         let type_val = retrieved
             .get_first(file_type)
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string();
+        let mime_val = retrieved
+            .get_first(fields.mime_type)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
         let modified_val = retrieved
             .get_first(modified_time)
             .and_then(|v| v.as_i64())
             .unwrap_or(0i64);
+        // 命中的段落（页、幻灯片、工作表等）
+        let passage = passage_doc.as_ref().and_then(|d| {
+            Some(PassagePayload {
+                kind: d.get_first(fields.passage_kind)?.as_str()?.to_string(),
+                index: d.get_first(fields.passage_no)?.as_u64()?,
+                name: d
+                    .get_first(fields.passage_name)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                hits: hit.passage_hits,
+            })
+        });
+        // Markdown 文件返回命中位置之前最近的标题，段落内没有标题时使用段落开始处所在的标题
+        let first_match = |text: &str| match &regex {
+            Some(re) => re.find(text).map(|m| m.start()),
+            None => first_match_byte(text, &query),
+        };
+        let section = if type_val == "md" {
+            first_match(&content_val)
+                .and_then(|pos| markdown::nearest_heading(&content_val, pos))
+                .or_else(|| passage.as_ref().and_then(|p| p.name.clone()))
+        } else {
+            None
+        };
+        // 代码文件返回命中符号的定义行号，按行分块的文件返回分块内命中的行号，正则搜索返回首个匹配所在的行号
+        let line_start = content_doc.get_first(fields.line_start).and_then(|v| v.as_u64());
+        let lines = match (line_start, &regex) {
+            (Some(start), Some(re)) => matched_lines(&content_val, start, re),
+            (Some(start), None) => matched_chunk_lines(&content_val, start, &query),
+            (None, _) => Vec::new(),
+        };
+        let line = match &regex {
+            Some(re) => lines
+                .first()
+                .copied()
+                .or_else(|| matched_lines(&content_val, 1, re).first().copied()),
+            None => matched_symbol_line(retrieved, fields.symbol, fields.symbol_line, &query)
+                .or_else(|| lines.first().copied()),
+        }
+        .or(line_start);
+        // 图片返回拍摄时间、相机、尺寸和 GPS 坐标
+        let image = if retrieved.get_first(fields.width).is_some()
+            || retrieved.get_first(fields.taken_at).is_some()
+        {
+            let gps = retrieved
+                .get_first(fields.gps_lat)
+                .and_then(|v| v.as_f64())
+                .zip(retrieved.get_first(fields.gps_lon).and_then(|v| v.as_f64()));
+            Some(ImageMetaPayload {
+                taken_at: retrieved.get_first(fields.taken_at).and_then(|v| v.as_i64()),
+                camera: retrieved
+                    .get_first(fields.camera)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                width: retrieved.get_first(fields.width).and_then(|v| v.as_u64()),
+                height: retrieved.get_first(fields.height).and_then(|v| v.as_u64()),
+                gps,
+            })
+        } else {
+            None
+        };
+        // 音视频返回标签中的标题、艺术家、专辑、年份和时长
+        let media_text = |field: Field| {
+            retrieved
+                .get_first(field)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+        let media = if is_audio_ext(&type_val) || is_video_ext(&type_val) {
+            Some(MediaMetaPayload {
+                title: media_text(fields.meta_title),
+                artist: media_text(fields.artist),
+                album: media_text(fields.album),
+                year: retrieved.get_first(fields.year).and_then(|v| v.as_u64()),
+                duration: retrieved.get_first(fields.duration).and_then(|v| v.as_u64()),
+            })
+        } else {
+            None
+        };
+        // CSV/TSV 文件返回命中的行号
+        let row = match &regex {
+            _ if !matches!(type_val.as_str(), "csv" | "tsv") => None,
+            Some(re) => matched_lines(&content_val, 1, re).into_iter().find(|&l| l > 1),
+            None => matched_csv_row(&content_val, &column_query),
+        };
+        // 拼音命中时返回对应的原始汉字，繁简、全半角写法与查询不同的命中返回原文写法，供前端高亮
+        let mut highlight_terms: Vec<String> = Vec::new();
+        for term in &pinyin_terms {
+            highlight_terms.extend(pinyin_matches(&title_val, term));
+            if pinyin_fields.contains(&fields.content_pinyin) {
+                highlight_terms.extend(pinyin_matches(&content_val, term));
+            }
+        }
+        for term in &query_terms {
+            highlight_terms.extend(normalized_matches(&title_val, term));
+            highlight_terms.extend(normalized_matches(&content_val, term));
+        }
+        let mut seen = HashSet::new();
+        highlight_terms.retain(|t| seen.insert(t.clone()));
         let mut highlights = Vec::new();
-        if !content_val.is_empty() {
-            let snippet = snippet_with_highlight(&content_val, &query, 60, 140);
+        if let Some(re) = &regex {
+            // 正则搜索按实际匹配到的片段高亮
+            highlight_terms = matched_terms(&[&title_val, &content_val], re);
+            let snippet = snippet_with_regex(&content_val, re, 60, 140);
+            if !snippet.is_empty() {
+                highlights.push(snippet);
+            }
+        } else if !content_val.is_empty() {
+            // 内容中没有查询原文而有拼音命中时，以命中的汉字生成摘要
+            let has_query = content_val.to_lowercase().contains(&query.to_lowercase());
+            let snippet_query = match highlight_terms.iter().find(|t| content_val.contains(&t[..])) {
+                Some(t) if !has_query => t.clone(),
+                _ => query.clone(),
+            };
+            let snippet = snippet_with_highlight(&content_val, &snippet_query, 60, 140);
             if !snippet.is_empty() {
                 highlights.push(snippet);
             }
@@ -195,10 +877,274 @@ pub fn do_search_index(
             content: content_val,
             file_path: path_val,
             file_type: type_val,
+            mime_type: mime_val,
             modified_time: modified_val,
             score: (score / if max_score > 0.0 { max_score } else { 1.0 }).min(1.0),
             highlights,
+            highlight_terms,
+            section,
+            line,
+            lines,
+            row,
+            passage,
+            image,
+            media,
         });
     }
-    Ok(SearchResponsePayload { results, total_count })
+    Ok(SearchResponsePayload {
+        results,
+        total_count,
+        truncated,
+        facets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{JsonObjectOptions, TextFieldIndexing, STORED, STRING, TEXT};
+
+    fn test_schema() -> Schema {
+        let mut builder = Schema::builder();
+        builder.add_text_field("title", TEXT);
+        builder.add_text_field("file_type", STRING);
+        builder.add_json_field("columns", JsonObjectOptions::default().set_indexing_options(TextFieldIndexing::default()));
+        builder.build()
+    }
+
+    #[test]
+    fn test_rewrite_dotted_column() {
+        let schema = test_schema();
+        let rewritten = rewrite_column_queries("V1.2:beta", &schema, |name| name == "v1.2");
+        assert_eq!(rewritten, "columns.v1\\.2:beta");
+        // 转义后的表头整体作为一级 JSON 路径
+        let columns = schema.get_field("columns").unwrap();
+        let parser = tantivy::query::QueryParser::for_index(
+            &tantivy::Index::create_in_ram(schema.clone()),
+            vec![columns],
+        );
+        let terms = |q: &str| {
+            let query = parser.parse_query(q).unwrap();
+            let mut terms = Vec::new();
+            query.query_terms(&mut |term, _| terms.push(term.clone()));
+            terms
+        };
+        let mut expected = Term::from_field_json_path(columns, "v1\\.2", false);
+        expected.append_type_and_str("beta");
+        assert_eq!(terms(&rewritten), vec![expected.clone()]);
+        assert_ne!(terms("columns.v1.2:beta"), vec![expected]);
+    }
+
+    #[test]
+    fn test_rewrite_column_queries() {
+        let schema = test_schema();
+        let is_column = |name: &str| matches!(name, "city" | "type" | "http");
+        let rewrite = |q: &str| rewrite_column_queries(q, &schema, is_column);
+        assert_eq!(rewrite("City:北京 +city:上海"), "columns.city:北京 +columns.city:上海");
+        assert_eq!(rewrite("(city:北京 OR city:上海)"), "(columns.city:北京 OR columns.city:上海)");
+        // 时间、网址、筛选操作符（含否定形式）、已有字段、引号内的文本以及不是表头的词保持不变
+        assert_eq!(rewrite("meeting 10:30"), "meeting 10:30");
+        assert_eq!(rewrite("http://example.com"), "http://example.com");
+        assert_eq!(rewrite("-type:pdf type:docx"), "-type:pdf type:docx");
+        assert_eq!(rewrite("title:报告 \"city:北京\""), "title:报告 \"city:北京\"");
+        assert_eq!(rewrite("country:中国 city:"), "country:中国 city:");
+    }
+
+    #[test]
+    fn test_matched_csv_row() {
+        // 表头前有空行，行号按源文件计算
+        let content = "\nName\tCity\n张三\t北京\n李四\t上海\n王五\t上海北京路\n";
+        assert_eq!(matched_csv_row(content, "columns.city:上海"), Some(4));
+        assert_eq!(matched_csv_row(content, "+columns.city:\"北京\""), Some(3));
+        // 列查询只匹配该列，其他列包含查询词不算
+        assert_eq!(matched_csv_row(content, "columns.name:北京"), None);
+        assert_eq!(matched_csv_row(content, "columns.name:王 columns.city:北京"), Some(5));
+        // 没有列查询时任一单元格包含查询词即可，排除的词不参与匹配
+        assert_eq!(matched_csv_row(content, "李四 -张三"), Some(4));
+        assert_eq!(matched_csv_row(content, "columns.missing:上海"), Some(4));
+        assert_eq!(matched_csv_row("", "北京"), None);
+    }
+
+    #[test]
+    fn test_matched_chunk_lines() {
+        let content = "start\nERROR disk full\nok\nwarn: Error retry\n";
+        // 行号从分块的起始行算起，忽略大小写，排除的词不参与匹配
+        assert_eq!(matched_chunk_lines(content, 101, "error -ok"), vec![102, 104]);
+        assert_eq!(matched_chunk_lines(content, 1, "content:\"disk\""), vec![2]);
+        assert!(matched_chunk_lines(content, 1, "missing").is_empty());
+        let many = "hit\n".repeat(30);
+        assert_eq!(matched_chunk_lines(&many, 1, "hit").len(), 20);
+    }
+
+    #[test]
+    fn test_dir_filters() {
+        let mut builder = Schema::builder();
+        let name = builder.add_text_field("name", STORED);
+        let dirs = builder.add_text_field("dirs", STRING);
+        let index = tantivy::Index::create_in_ram(builder.build());
+        let mut writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for path in [
+            "D:\\合同\\2024\\a.txt",
+            "D:\\合同\\archive\\b.txt",
+            "/home/u/Re\u{301}sume\u{301}/c.txt",
+            "/home/u/notes/d.txt",
+        ] {
+            let mut doc = TantivyDocument::default();
+            doc.add_text(name, path.rsplit(['/', '\\']).next().unwrap());
+            for dir in crate::unicode_norm::ancestor_dir_keys(std::path::Path::new(path)) {
+                doc.add_text(dirs, dir);
+            }
+            writer.add_document(doc).unwrap();
+        }
+        writer.commit().unwrap();
+        let searcher = index.reader().unwrap().searcher();
+        let names = |query: &dyn Query| {
+            let docs = searcher.search(query, &tantivy::collector::DocSetCollector).unwrap();
+            let mut names: Vec<String> = docs
+                .into_iter()
+                .map(|addr| {
+                    let doc: TantivyDocument = searcher.doc(addr).unwrap();
+                    doc.get_first(name).and_then(|v| v.as_str()).unwrap().to_string()
+                })
+                .collect();
+            names.sort();
+            names
+        };
+        let include = |dir: &str| names(dir_query(dirs, dir).unwrap().unwrap().as_ref());
+        let exclude = |dir: &str| {
+            names(&BooleanQuery::new(vec![
+                (Occur::Must, Box::new(AllQuery) as Box<dyn Query>),
+                (Occur::MustNot, dir_query(dirs, dir).unwrap().unwrap()),
+            ]))
+        };
+        // 绝对路径：Windows 分隔符、大小写和末尾分隔符不影响匹配，包含子目录
+        assert_eq!(include("d:\\合同\\"), vec!["a.txt", "b.txt"]);
+        assert_eq!(include("D:/合同/2024"), vec!["a.txt"]);
+        assert!(include("D:\\合").is_empty());
+        assert_eq!(exclude("D:\\合同"), vec!["c.txt", "d.txt"]);
+        // 相对路径匹配任意位置的同名目录，不匹配目录名的一部分
+        assert_eq!(include("archive/"), vec!["b.txt"]);
+        assert_eq!(include("合同\\archive"), vec!["b.txt"]);
+        assert!(include("chive").is_empty());
+        // 组合形式（NFC）与分解形式（NFD）的目录名互相匹配
+        assert_eq!(include("/home/u/Résumé"), vec!["c.txt"]);
+        assert_eq!(include("Re\u{301}sume\u{301}"), vec!["c.txt"]);
+        assert_eq!(exclude("résumé/"), vec!["a.txt", "b.txt", "d.txt"]);
+        assert!(dir_query(dirs, " \\ ").unwrap().is_none());
+
+        // 收集器中的目录匹配规则与查询相同
+        let matches = |dir: &str, path: &str| {
+            let re = dir_regex(dir).unwrap().unwrap();
+            crate::unicode_norm::ancestor_dir_keys(std::path::Path::new(path))
+                .iter()
+                .any(|d| re.is_match(d))
+        };
+        assert!(matches("d:\\合同\\", "D:\\合同\\2024\\a.txt"));
+        assert!(!matches("D:\\合", "D:\\合同\\2024\\a.txt"));
+        assert!(matches("合同\\2024", "D:\\合同\\2024\\a.txt"));
+        assert!(!matches("chive", "D:\\合同\\archive\\b.txt"));
+        assert!(matches("/home/u/Résumé", "/home/u/Re\u{301}sume\u{301}/c.txt"));
+        assert!(dir_regex("").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_collect_facets() {
+        let now = chrono::Utc::now().timestamp_millis();
+        let file = |file_type: &str, root_dir: &str, modified_time: Option<i64>, ok: [bool; 3]| {
+            FileFacets {
+                file_type: Some(file_type.to_string()),
+                root_dir: Some(root_dir.to_string()),
+                modified_time,
+                type_ok: ok[0],
+                dir_ok: ok[1],
+                date_ok: ok[2],
+            }
+        };
+        let files = [
+            file("pdf", "/docs", Some(now), [true, true, true]),
+            file("pdf", "/docs", Some(0), [true, true, true]),
+            file("docx", "/work", None, [true, true, true]),
+            // 不满足类型筛选：只计入类型分面
+            file("txt", "/docs", Some(now), [false, true, true]),
+            // 不满足目录筛选：只计入目录分面
+            file("pdf", "/other", Some(now), [true, false, true]),
+            // 不满足时间筛选：只计入时间分面
+            file("pdf", "/docs", Some(0), [true, true, false]),
+        ];
+        let facets = collect_facets(files.iter());
+        let counts = |v: &[FacetCountPayload]| {
+            v.iter().map(|f| (f.value.clone(), f.count)).collect::<Vec<_>>()
+        };
+        let pairs = |v: &[(&str, usize)]| {
+            v.iter().map(|(s, n)| (s.to_string(), *n)).collect::<Vec<_>>()
+        };
+        // 按数量从多到少，数量相同时按取值排序
+        assert_eq!(
+            counts(&facets.file_types),
+            pairs(&[("pdf", 2), ("docx", 1), ("txt", 1)])
+        );
+        assert_eq!(
+            counts(&facets.directories),
+            pairs(&[("/docs", 2), ("/other", 1), ("/work", 1)])
+        );
+        // 没有修改时间的文件计入 older
+        assert_eq!(counts(&facets.dates), pairs(&[("older", 3), ("today", 1)]));
+        assert!(collect_facets([].iter()).file_types.is_empty());
+    }
+
+    #[test]
+    fn test_sort_hits() {
+        let hit = |score: f32, modified_time: Option<i64>, name: &str| FileHit {
+            doc: Some((score, tantivy::DocAddress::new(0, 0))),
+            modified_time,
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+        let mut hits = vec![
+            ("/b.txt".to_string(), hit(1.0, Some(10), "b.txt")),
+            ("/a.txt".to_string(), hit(1.0, Some(10), "a.txt")),
+            ("/c.txt".to_string(), hit(3.0, None, "c.txt")),
+            ("/d.txt".to_string(), hit(2.0, Some(20), "d.txt")),
+        ];
+        let paths = |hits: &[(String, FileHit)]| {
+            hits.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>().join(" ")
+        };
+        sort_hits(&mut hits, SearchSort::Relevance);
+        assert_eq!(paths(&hits), "/c.txt /d.txt /a.txt /b.txt");
+        // 缺少修改时间的文件排在最后，时间和得分都相同时按路径
+        sort_hits(&mut hits, SearchSort::Newest);
+        assert_eq!(paths(&hits), "/d.txt /a.txt /b.txt /c.txt");
+        sort_hits(&mut hits, SearchSort::Oldest);
+        assert_eq!(paths(&hits), "/a.txt /b.txt /d.txt /c.txt");
+        sort_hits(&mut hits, SearchSort::Name);
+        assert_eq!(paths(&hits), "/a.txt /b.txt /c.txt /d.txt");
+    }
+
+    #[test]
+    fn test_plain_query_terms() {
+        assert_eq!(
+            plain_query_terms("Router AND title:\"Config\" -draft NOT (+Prefix*) type:pdf"),
+            vec!["router", "config", "prefix", "pdf"]
+        );
+        assert!(plain_query_terms("OR -x \"\"").is_empty());
+    }
+
+    #[test]
+    fn test_matched_symbol_line() {
+        let mut builder = Schema::builder();
+        let symbol = builder.add_text_field("symbol", STORED);
+        let symbol_line = builder.add_u64_field("symbol_line", STORED);
+        let mut doc = TantivyDocument::default();
+        for (name, line) in [("parseConfig", 3u64), ("Config", 12), ("load", 20)] {
+            doc.add_text(symbol, name);
+            doc.add_u64(symbol_line, line);
+        }
+        let line = |q: &str| matched_symbol_line(&doc, symbol, symbol_line, q);
+        // 完全匹配优先于包含匹配，忽略大小写
+        assert_eq!(line("config"), Some(12));
+        assert_eq!(line("symbol:PARSE"), Some(3));
+        assert_eq!(line("load -config"), Some(20));
+        assert_eq!(line("missing"), None);
+        assert_eq!(line("-config"), None);
+    }
 }
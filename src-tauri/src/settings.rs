@@ -0,0 +1,99 @@
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri::Manager;
+
+use crate::file_types::FileTypeGroup;
+
+/// 后端用到的应用设置，读取自前端保存的 settings.json，缺失的项使用默认值
+#[derive(Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackendSettings {
+    pub indexing: IndexingSettings,
+    pub search: SearchSettings,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchSettings {
+    /// 自定义文件类型分组，与内置分组合并
+    pub file_type_groups: Vec<FileTypeGroup>,
+    /// 查询中未写运算符的多个词之间的默认运算
+    pub default_operator: DefaultOperator,
+}
+
+/// 查询的默认运算
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultOperator {
+    /// 所有词都必须命中；长文档拆分为段落后标题和正文不在同一个文档中，同时包含两者的词可能查不到
+    And,
+    /// 命中任意一个词即可（默认），命中词越多得分越高
+    #[default]
+    Or,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IndexingSettings {
+    /// 大文本文件（日志等）是否按行分块索引
+    pub line_chunking: bool,
+    /// 每个分块包含的行数
+    pub line_chunk_size: usize,
+    /// 超过该大小（字节）的文本文件才按行分块
+    pub line_chunk_min_file_size: u64,
+    /// 长文档按该字符数拆分为段落子文档，为 0（默认）时不拆分；拆分方式变化后需要重建索引
+    pub passage_size: usize,
+    /// 中文分词方式，切换后需要重建索引
+    pub tokenizer: ChineseTokenizer,
+    /// 词典分词使用的用户词典文件路径
+    pub user_dictionary: Option<String>,
+    /// 是否为标题建立拼音索引，可用全拼或首字母搜索
+    pub pinyin: bool,
+    /// 开启拼音索引时是否同时为内容建立拼音索引（索引会明显变大）
+    pub pinyin_content: bool,
+    /// 模糊搜索是否同时匹配内容中的单词（索引会变大），关闭时只匹配文件名、标题等中的单词
+    pub fuzzy_content: bool,
+    /// 是否为正则搜索建立原文词项索引（默认开启，索引会变大），关闭时不能使用正则搜索
+    pub regex_index: bool,
+    /// 英文等拼音文字的分析语言（如 `english`），设置后按单词分词并去停用词、提取词干，为空时按 n-gram 分词
+    pub text_language: Option<String>,
+}
+
+/// 中文分词方式
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChineseTokenizer {
+    /// 2-3 字符 n-gram，召回率高但索引较大
+    #[default]
+    Ngram,
+    /// 基于词典的 jieba 分词
+    Jieba,
+}
+
+impl Default for IndexingSettings {
+    fn default() -> Self {
+        Self {
+            line_chunking: false,
+            line_chunk_size: 200,
+            line_chunk_min_file_size: 1024 * 1024,
+            passage_size: 0,
+            tokenizer: ChineseTokenizer::Ngram,
+            user_dictionary: None,
+            pinyin: false,
+            pinyin_content: false,
+            fuzzy_content: false,
+            regex_index: true,
+            text_language: None,
+        }
+    }
+}
+
+/// 读取应用配置目录下的 settings.json，读取或解析失败时返回默认设置
+pub fn load_settings(app: &AppHandle) -> BackendSettings {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .and_then(|dir| std::fs::read_to_string(dir.join("settings.json")).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
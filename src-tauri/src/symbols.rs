@@ -0,0 +1,127 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// 源代码中的符号定义（函数、结构体、类、接口等）
+#[derive(Debug, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    /// 定义所在行号，从 1 开始
+    pub line: u64,
+}
+
+/// 各语言的符号定义规则，每条规则中第一个捕获组为符号名
+static RUST_RULES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    vec![
+        Regex::new(r#"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:(?:async|const|unsafe|extern\s+"[^"]*")\s+)*(?:fn|struct|enum|trait|type|mod|union)\s+([A-Za-z_][A-Za-z0-9_]*)"#).unwrap(),
+        Regex::new(r"^\s*macro_rules!\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap(),
+    ]
+});
+
+static JS_RULES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    vec![
+        Regex::new(r"^\s*(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?:function\*?|class|interface|type|enum)\s+([A-Za-z_$][\w$]*)").unwrap(),
+        Regex::new(r"^\s*(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*(?::[^=]+)?=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*(?::[^=]+)?=>|[A-Za-z_$][\w$]*\s*=>)").unwrap(),
+    ]
+});
+
+static PY_RULES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    vec![Regex::new(r"^\s*(?:async\s+)?(?:def|class)\s+([A-Za-z_]\w*)").unwrap()]
+});
+
+static GO_RULES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    vec![
+        Regex::new(r"^func\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)").unwrap(),
+        Regex::new(r"^type\s+([A-Za-z_]\w*)\s+(?:struct|interface)\b").unwrap(),
+    ]
+});
+
+static JAVA_RULES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    vec![
+        Regex::new(r"^\s*(?:(?:public|private|protected|static|final|abstract|sealed)\s+)*(?:class|interface|enum|record)\s+([A-Za-z_]\w*)").unwrap(),
+        Regex::new(r"^\s*(?:(?:public|private|protected|static|final|abstract|synchronized)\s+)+[\w<>\[\], ?]+\s+([A-Za-z_]\w*)\s*\(").unwrap(),
+    ]
+});
+
+/// 支持符号提取的源代码扩展名
+pub fn is_code_ext(ext: &str) -> bool {
+    rules_for(ext).is_some()
+}
+
+fn rules_for(ext: &str) -> Option<&'static [Regex]> {
+    match ext {
+        "rs" => Some(RUST_RULES.as_slice()),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Some(JS_RULES.as_slice()),
+        "py" => Some(PY_RULES.as_slice()),
+        "go" => Some(GO_RULES.as_slice()),
+        "java" => Some(JAVA_RULES.as_slice()),
+        _ => None,
+    }
+}
+
+/// 按扩展名逐行提取源代码中的符号定义
+pub fn extract_symbols(ext: &str, text: &str) -> Vec<Symbol> {
+    let Some(rules) = rules_for(ext) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        for re in rules {
+            if let Some(name) = re.captures(line).and_then(|c| c.get(1)) {
+                out.push(Symbol {
+                    name: name.as_str().to_string(),
+                    line: i as u64 + 1,
+                });
+                break;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(ext: &str, src: &str) -> Vec<(String, u64)> {
+        extract_symbols(ext, src)
+            .into_iter()
+            .map(|s| (s.name, s.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_rust_symbols() {
+        let src = "use std::fs;\n\npub struct IndexFields {\n}\n\npub(crate) async fn do_search() {}\nenum Kind { A }\n";
+        assert_eq!(
+            names("rs", src),
+            vec![
+                ("IndexFields".to_string(), 3),
+                ("do_search".to_string(), 6),
+                ("Kind".to_string(), 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_ts_symbols() {
+        let src = "export interface SearchResult {}\nexport const SearchPage: React.FC = () => {};\nclass Foo {}\nconst x = 1;\n";
+        assert_eq!(
+            names("ts", src),
+            vec![
+                ("SearchResult".to_string(), 1),
+                ("SearchPage".to_string(), 2),
+                ("Foo".to_string(), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_python_and_go_symbols() {
+        assert_eq!(names("py", "class A:\n    def run(self):\n"), vec![("A".to_string(), 1), ("run".to_string(), 2)]);
+        assert_eq!(
+            names("go", "type Server struct {\n}\nfunc (s *Server) Start() {}\n"),
+            vec![("Server".to_string(), 1), ("Start".to_string(), 3)]
+        );
+        assert!(extract_symbols("txt", "fn main() {}").is_empty());
+    }
+}
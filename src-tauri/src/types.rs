@@ -19,6 +19,38 @@ pub struct IndexProgressPayload {
     pub indexed_files: usize,
     pub index_size: u64,
     pub last_updated: i64,
+    /// 重建失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 文件名索引的重建进度，遍历时不预先统计文件总数
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameIndexProgressPayload {
+    pub is_indexing: bool,
+    pub indexed_files: usize,
+    pub last_updated: i64,
+    /// 重建失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 文件名搜索的单个结果
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameResultPayload {
+    pub name: String,
+    pub file_path: String,
+    /// 扩展名（小写），不限于支持内容索引的类型
+    pub file_type: String,
+    pub file_size: u64,
+    pub modified_time: i64,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FilenameSearchResponsePayload {
+    pub results: Vec<FilenameResultPayload>,
+    pub total_count: usize,
 }
 
 #[derive(Serialize, Clone)]
@@ -29,9 +61,40 @@ pub struct SearchResultPayload {
     pub content: String,
     pub file_path: String,
     pub file_type: String,
+    /// 按文件头部签名识别出的 MIME 类型
+    pub mime_type: Option<String>,
     pub modified_time: i64,
     pub score: f32,
     pub highlights: Vec<String>,
+    /// 需要额外高亮的原文片段（如拼音查询命中的汉字）
+    pub highlight_terms: Vec<String>,
+    /// 命中位置所在的章节（Markdown 中最近的上级标题）
+    pub section: Option<String>,
+    /// 命中位置所在的行号（代码文件中为符号定义所在行）
+    pub line: Option<u64>,
+    /// 按行分块索引的文件中，分块内所有命中的行号
+    pub lines: Vec<u64>,
+    /// CSV/TSV 中命中的行号（表头为第 1 行）
+    pub row: Option<u64>,
+    /// 长文档中得分最高的命中段落
+    pub passage: Option<PassagePayload>,
+    /// 图片元数据
+    pub image: Option<ImageMetaPayload>,
+    /// 音视频元数据
+    pub media: Option<MediaMetaPayload>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PassagePayload {
+    /// 段落类型：page、slide、sheet、lines、text
+    pub kind: String,
+    /// 段落序号（第几页、第几张幻灯片等），从 1 开始
+    pub index: u64,
+    /// 段落名称，如工作表名、所在章节标题
+    pub name: Option<String>,
+    /// 该文件中命中的段落数
+    pub hits: u32,
 }
 
 #[derive(Serialize, Clone)]
@@ -39,15 +102,89 @@ pub struct SearchResultPayload {
 pub struct SearchResponsePayload {
     pub results: Vec<SearchResultPayload>,
     pub total_count: usize,
+    /// 命中文件数超过单次搜索的归组上限，只能分页浏览排名最前的文件
+    pub truncated: bool,
+    /// 各分类的命中文件数，仅在请求时返回
+    pub facets: Option<SearchFacetsPayload>,
+}
+
+/// 搜索失败时返回给前端的错误
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchErrorPayload {
+    /// 错误类型：emptyValue、invalidDate、invalidSize 为查询操作符错误，invalidRegex、regexNoIndex、regexTooBroad 为正则表达式无效、索引中没有原文词项、表达式无法筛选候选文档，internal 为其他错误
+    pub code: String,
+    /// 出错的查询片段
+    pub token: Option<String>,
+    /// 本地化的错误信息
+    pub message: String,
 }
 
+impl From<String> for SearchErrorPayload {
+    fn from(message: String) -> Self {
+        Self {
+            code: "internal".to_string(),
+            token: None,
+            message,
+        }
+    }
+}
 
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCountPayload {
+    pub value: String,
+    pub count: usize,
+}
 
-#[derive(Deserialize, Clone)]
+/// 命中文件按文件类型、所属索引目录和修改时间分桶的计数，均按数量从多到少排列
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFacetsPayload {
+    pub file_types: Vec<FacetCountPayload>,
+    pub directories: Vec<FacetCountPayload>,
+    /// 修改时间：按本地日历分为互不包含的 today、week、month、older，week 不含今天，month 不含本周
+    pub dates: Vec<FacetCountPayload>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMetaPayload {
+    pub taken_at: Option<i64>,
+    pub camera: Option<String>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    /// GPS 坐标（纬度、经度）
+    pub gps: Option<(f64, f64)>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaMetaPayload {
+    /// 标签中的标题
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub year: Option<u64>,
+    /// 时长（秒）
+    pub duration: Option<u64>,
+}
+
+#[derive(Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchFiltersCmd {
     pub file_types: Option<Vec<String>>,
     pub date_range: Option<DateRangeCmd>,
+    /// 图片拍摄时间区间
+    pub taken_range: Option<DateRangeCmd>,
+    /// 相机品牌或型号，多个单词时需全部匹配
+    pub camera: Option<String>,
+    /// 只搜索这些目录下的文件（满足其一即可），绝对路径匹配该目录，相对路径匹配任意位置的同名目录
+    pub include_dirs: Option<Vec<String>>,
+    /// 排除这些目录下的文件，匹配方式同 include_dirs
+    pub exclude_dirs: Option<Vec<String>>,
+    /// 文件大小区间（字节）
+    pub file_size_range: Option<SizeRangeCmd>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -57,3 +194,26 @@ pub struct DateRangeCmd {
     pub end: Option<i64>,
 }
 
+/// 搜索结果的排序方式，除相关度外得分相同时按相关度排序
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    /// 修改时间从新到旧
+    Newest,
+    Oldest,
+    /// 文件大小从大到小
+    Largest,
+    Smallest,
+    /// 文件名 A–Z
+    Name,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeRangeCmd {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
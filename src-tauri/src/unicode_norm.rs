@@ -0,0 +1,73 @@
+use std::path::Path;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// 转为 NFC 形式；macOS 创建的文件名多为 NFD（如 `é` 存为 `e` 加组合重音符），
+/// 索引和查询统一使用 NFC 才能互相匹配
+pub fn nfc(text: &str) -> String {
+    match is_nfc_quick(text.chars()) {
+        IsNormalized::Yes => text.to_string(),
+        _ => text.nfc().collect(),
+    }
+}
+
+/// 比较路径时使用的键（NFC 形式的路径字符串），同一文件的 NFC、NFD 路径得到相同的键
+///
+/// 存储的 file_path 保持文件系统中的原始路径，以便打开文件
+pub fn path_key(path: &Path) -> String {
+    nfc(&path.to_string_lossy())
+}
+
+/// 目录筛选使用的键：NFC、分隔符统一为 `/`、小写，去掉末尾的分隔符，如 `D:\合同\2024\` 转为 `d:/合同/2024`
+pub fn dir_key(dir: &str) -> String {
+    nfc(dir.trim())
+        .replace('\\', "/")
+        .to_lowercase()
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// 是否为绝对路径形式的目录键（`/` 开头或 `d:` 这样的盘符开头）
+pub fn is_absolute_dir_key(key: &str) -> bool {
+    key.starts_with('/') || key.chars().nth(1) == Some(':')
+}
+
+/// 文件所在的各级目录的键（不含根目录），如 `/home/a/b.txt` 得到 `/home`、`/home/a`
+pub fn ancestor_dir_keys(path: &Path) -> Vec<String> {
+    let key = dir_key(&path.to_string_lossy());
+    key.match_indices('/')
+        .map(|(i, _)| &key[..i])
+        .filter(|d| !d.trim_matches('/').is_empty())
+        .map(|d| d.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nfc() {
+        let nfd = "Re\u{301}sume\u{301} 文档.txt";
+        assert_eq!(nfc(nfd), "Résumé 文档.txt");
+        assert_eq!(nfc("Résumé"), "Résumé");
+        assert_eq!(
+            path_key(Path::new("/tmp/cafe\u{301}/a.txt")),
+            path_key(Path::new("/tmp/café/a.txt"))
+        );
+    }
+
+    #[test]
+    fn test_dir_keys() {
+        assert_eq!(dir_key("D:\\合同\\2024\\"), "d:/合同/2024");
+        assert!(is_absolute_dir_key(&dir_key("D:\\合同")));
+        assert!(!is_absolute_dir_key(&dir_key("archive/")));
+        assert_eq!(
+            ancestor_dir_keys(Path::new("/home/U/docs/a.txt")),
+            vec!["/home", "/home/u", "/home/u/docs"]
+        );
+        assert_eq!(
+            ancestor_dir_keys(Path::new("D:\\合同\\2024\\a.docx")),
+            vec!["d:", "d:/合同", "d:/合同/2024"]
+        );
+    }
+}
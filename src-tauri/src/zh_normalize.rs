@@ -0,0 +1,152 @@
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// 全角字符转半角：全角 ASCII（U+FF01–U+FF5E）和全角空格
+fn fold_width(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        _ => c,
+    }
+}
+
+/// 繁体转简体、全角转半角，如“網絡ＡＢＣ１２３”转为“网络ABC123”
+pub fn normalize_zh(text: &str) -> String {
+    if text.is_ascii() {
+        return text.to_string();
+    }
+    let folded: String = text.chars().map(fold_width).collect();
+    fast2s::convert(&folded)
+}
+
+/// 归一化文本，同时返回归一化文本中每个字符边界（及末尾）对应的原文字节偏移，
+/// 供在归一化文本上分词、偏移仍需指向原文的分词器使用
+///
+/// 繁简转换改变了字符数时无法逐字对应，此时只做全角转半角
+pub fn normalize_zh_with_offsets(text: &str) -> (String, Vec<usize>) {
+    let mut normalized = normalize_zh(text);
+    if normalized.chars().count() != text.chars().count() {
+        normalized = text.chars().map(fold_width).collect();
+    }
+    let mut offsets = vec![text.len(); normalized.len() + 1];
+    let mut pos = 0usize;
+    for ((orig, _), c) in text.char_indices().zip(normalized.chars()) {
+        offsets[pos] = orig;
+        pos += c.len_utf8();
+    }
+    (normalized, offsets)
+}
+
+/// 查找文本中归一化后与查询词相同、但写法不同的原文片段（忽略大小写），用于高亮，
+/// 如查询“网络”返回“網絡”
+pub fn normalized_matches(text: &str, term: &str) -> Vec<String> {
+    let term = normalize_zh(term).to_lowercase();
+    if term.is_empty() || text.is_ascii() {
+        return Vec::new();
+    }
+    let (normalized, offsets) = normalize_zh_with_offsets(text);
+    let lower = normalized.to_lowercase();
+    // 小写转换改变了字节长度时偏移无法对应
+    if lower.len() != normalized.len() {
+        return Vec::new();
+    }
+    let mut found: Vec<String> = Vec::new();
+    for (pos, _) in lower.match_indices(term.as_str()) {
+        let s = &text[offsets[pos]..offsets[pos + term.len()]];
+        if s.to_lowercase() != term && !found.iter().any(|f| f == s) {
+            found.push(s.to_string());
+        }
+    }
+    found
+}
+
+/// 繁简、全半角归一化过滤器，索引和查询时使用同一过滤器，繁体与简体、全角与半角可互相匹配
+///
+/// 只改变词项，存储的原文和词项偏移保持不变
+#[derive(Clone)]
+pub struct ZhNormalizer;
+
+impl TokenFilter for ZhNormalizer {
+    type Tokenizer<T: Tokenizer> = ZhNormalizerFilter<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> ZhNormalizerFilter<T> {
+        ZhNormalizerFilter { tokenizer }
+    }
+}
+
+#[derive(Clone)]
+pub struct ZhNormalizerFilter<T> {
+    tokenizer: T,
+}
+
+impl<T: Tokenizer> Tokenizer for ZhNormalizerFilter<T> {
+    type TokenStream<'a> = ZhNormalizerTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        ZhNormalizerTokenStream {
+            tail: self.tokenizer.token_stream(text),
+        }
+    }
+}
+
+pub struct ZhNormalizerTokenStream<T> {
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for ZhNormalizerTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let token = self.tail.token_mut();
+        if !token.text.is_ascii() {
+            token.text = normalize_zh(&token.text);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::tokenizer::{LowerCaser, NgramTokenizer, TextAnalyzer};
+
+    #[test]
+    fn test_zh_normalizer_filter() {
+        let mut analyzer = TextAnalyzer::builder(NgramTokenizer::new(2, 2, false).unwrap())
+            .filter(ZhNormalizer)
+            .filter(LowerCaser)
+            .build();
+        let text = "網絡ＡＢ";
+        let mut stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        while let Some(t) = stream.next() {
+            tokens.push((t.text.clone(), &text[t.offset_from..t.offset_to]));
+        }
+        assert_eq!(
+            tokens,
+            vec![
+                ("网络".to_string(), "網絡"),
+                ("络a".to_string(), "絡Ａ"),
+                ("ab".to_string(), "ＡＢ"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalized_matches() {
+        assert_eq!(normalized_matches("計算機網絡說明", "网络"), vec!["網絡"]);
+        assert_eq!(normalized_matches("型号ＡＢＣ１２３", "abc123"), vec!["ＡＢＣ１２３"]);
+        assert!(normalized_matches("网络说明", "网络").is_empty());
+        let (normalized, offsets) = normalize_zh_with_offsets("Ａ網");
+        assert_eq!(normalized, "A网");
+        assert_eq!((offsets[0], offsets[1], offsets[4]), (0, 3, 6));
+    }
+}
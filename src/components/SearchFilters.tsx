@@ -1,44 +1,99 @@
-import React from 'react';
-import { SearchFilters } from '../types';
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { FileTypeGroup, SearchFacets, SearchFilters } from '../types';
 import { useI18n } from '../i18n';
 
 interface SearchFiltersProps {
   filters: SearchFilters;
+  // 当前搜索的分面计数，未搜索时为空，不显示计数
+  facets?: SearchFacets | null;
   onFiltersChange: (filters: SearchFilters) => void;
 }
 
 export const SearchFiltersComponent: React.FC<SearchFiltersProps> = ({ 
   filters, 
+  facets,
   onFiltersChange 
 }) => {
   const { t } = useI18n();
-  const setDatePreset = (preset: 'any' | 'lastDay' | 'lastWeek' | 'lastMonth') => {
+  const setDatePreset = (preset: 'any' | 'today' | 'thisWeek' | 'thisMonth') => {
     if (preset === 'any') {
       onFiltersChange({ ...filters, dateRange: undefined, datePreset: 'any' });
       return;
     }
-    const now = Date.now();
+    // 按本地日历：今天零点、本周一零点、本月一日零点起，与后端的修改时间分桶一致
+    const d = new Date();
+    d.setHours(0, 0, 0, 0);
     const presets: Record<string, number> = {
-      lastDay: 86400000,
-      lastWeek: 604800000,
-      lastMonth: 2592000000,
+      today: d.getTime(),
+      thisWeek: new Date(d.getFullYear(), d.getMonth(), d.getDate() - ((d.getDay() + 6) % 7)).getTime(),
+      thisMonth: new Date(d.getFullYear(), d.getMonth(), 1).getTime(),
     };
-    const delta = presets[preset];
     onFiltersChange({
       ...filters,
-      dateRange: { start: now - delta, end: now },
+      dateRange: { start: presets[preset] },
       datePreset: preset,
     });
   };
-  const fileTypes = [
-    { value: 'txt', label: t('components.searchFilters.types.txt') },
-    { value: 'md', label: t('components.searchFilters.types.md') },
-    { value: 'pdf', label: t('components.searchFilters.types.pdf') },
-    { value: 'doc', label: t('components.searchFilters.types.doc') },
-    { value: 'xls', label: t('components.searchFilters.types.xls') },
-    { value: 'ppt', label: t('components.searchFilters.types.ppt') },
-    { value: 'plain', label: t('components.searchFilters.types.plain') },
-  ];
+  // 文件类型分组由后端提供（内置分组及设置中的自定义分组）
+  const [groups, setGroups] = useState<FileTypeGroup[]>([]);
+  useEffect(() => {
+    invoke<FileTypeGroup[]>('list_file_type_groups')
+      .then(setGroups)
+      .catch(() => setGroups([]));
+  }, []);
+  // 分面计数：文件类型分组为其各扩展名计数之和；后端的修改时间分桶互不包含，时间预设（今天、本周、本月起）为其前几个分桶之和
+  const facetCount = (list: { value: string; count: number }[] | undefined, values: string[]) =>
+    (list || []).filter((c) => values.includes(c.value)).reduce((sum, c) => sum + c.count, 0);
+  const countLabel = (count: number) => (facets ? ` · ${count}` : '');
+  const dateCounts: Record<string, number> = {
+    any: facetCount(facets?.dates, ['today', 'week', 'month', 'older']),
+    today: facetCount(facets?.dates, ['today']),
+    thisWeek: facetCount(facets?.dates, ['today', 'week']),
+    thisMonth: facetCount(facets?.dates, ['today', 'week', 'month']),
+  };
+  const fileTypes = groups.map((g) => {
+    const name = g.builtin ? t(`components.searchFilters.types.${g.id}`) : g.name || g.id;
+    const exts = g.extensions.slice(0, 3).map((e) => `.${e}`).join(', ');
+    return {
+      value: g.id,
+      label: `${name} (${exts}${g.extensions.length > 3 ? ', ...' : ''})`,
+      count: facetCount(facets?.fileTypes, g.extensions),
+    };
+  });
+
+  // 目录筛选每行一个目录，输入完成（失去焦点）后才更新筛选条件
+  const [includeDirs, setIncludeDirs] = useState((filters.includeDirs || []).join('\n'));
+  const [excludeDirs, setExcludeDirs] = useState((filters.excludeDirs || []).join('\n'));
+  useEffect(() => {
+    setIncludeDirs((filters.includeDirs || []).join('\n'));
+    setExcludeDirs((filters.excludeDirs || []).join('\n'));
+  }, [filters.includeDirs, filters.excludeDirs]);
+  const handleDirsChange = (key: 'includeDirs' | 'excludeDirs', value: string) => {
+    const dirs = value.split('\n').map((d) => d.trim()).filter(Boolean);
+    onFiltersChange({ ...filters, [key]: dirs.length > 0 ? dirs : undefined });
+  };
+
+  // 照片筛选：拍摄日期按本地时间，结束日期包含当天；相机输入完成（失去焦点）后才更新筛选条件
+  const [camera, setCamera] = useState(filters.camera || '');
+  useEffect(() => {
+    setCamera(filters.camera || '');
+  }, [filters.camera]);
+  const toDateInput = (ms?: number) => {
+    if (ms == null) return '';
+    const d = new Date(ms);
+    return `${d.getFullYear()}-${String(d.getMonth() + 1).padStart(2, '0')}-${String(d.getDate()).padStart(2, '0')}`;
+  };
+  const handleTakenChange = (key: 'start' | 'end', value: string) => {
+    const range = { ...filters.takenRange };
+    if (value) {
+      const [y, m, d] = value.split('-').map(Number);
+      range[key] = key === 'start' ? new Date(y, m - 1, d).getTime() : new Date(y, m - 1, d + 1).getTime() - 1;
+    } else {
+      delete range[key];
+    }
+    onFiltersChange({ ...filters, takenRange: range.start != null || range.end != null ? range : undefined });
+  };
 
   const handleFileTypeChange = (fileType: string, checked: boolean) => {
     const currentTypes = filters.fileTypes || [];
@@ -67,7 +122,7 @@ export const SearchFiltersComponent: React.FC<SearchFiltersProps> = ({
                 className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
               />
               <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">
-                {type.label}
+                {type.label}{countLabel(type.count)}
               </span>
             </label>
           ))}
@@ -87,48 +142,116 @@ export const SearchFiltersComponent: React.FC<SearchFiltersProps> = ({
               className="border-gray-300 text-primary-600 focus:ring-primary-500"
             />
             <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">
-              {t('components.searchFilters.any')}
+              {t('components.searchFilters.any')}{countLabel(dateCounts.any)}
             </span>
           </label>
           <label className="flex items-center">
             <input
               type="radio"
               name="dateRange"
-              checked={filters.datePreset === 'lastDay'}
-              onChange={() => setDatePreset('lastDay')}
+              checked={filters.datePreset === 'today'}
+              onChange={() => setDatePreset('today')}
               className="border-gray-300 text-primary-600 focus:ring-primary-500"
             />
             <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">
-              {t('components.searchFilters.lastDay')}
+              {t('components.searchFilters.today')}{countLabel(dateCounts.today)}
             </span>
           </label>
           <label className="flex items-center">
             <input
               type="radio"
               name="dateRange"
-              checked={filters.datePreset === 'lastWeek'}
-              onChange={() => setDatePreset('lastWeek')}
+              checked={filters.datePreset === 'thisWeek'}
+              onChange={() => setDatePreset('thisWeek')}
               className="border-gray-300 text-primary-600 focus:ring-primary-500"
             />
             <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">
-              {t('components.searchFilters.lastWeek')}
+              {t('components.searchFilters.thisWeek')}{countLabel(dateCounts.thisWeek)}
             </span>
           </label>
           <label className="flex items-center">
             <input
               type="radio"
               name="dateRange"
-              checked={filters.datePreset === 'lastMonth'}
-              onChange={() => setDatePreset('lastMonth')}
+              checked={filters.datePreset === 'thisMonth'}
+              onChange={() => setDatePreset('thisMonth')}
               className="border-gray-300 text-primary-600 focus:ring-primary-500"
             />
             <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">
-              {t('components.searchFilters.lastMonth')}
+              {t('components.searchFilters.thisMonth')}{countLabel(dateCounts.thisMonth)}
             </span>
           </label>
         </div>
       </div>
 
+      {/* Photo Filter */}
+      <div>
+        <h4 className="text-sm font-medium text-gray-900 dark:text-white mb-2">{t('components.searchFilters.photos')}</h4>
+        <div className="space-y-2">
+          <div className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+            <span>{t('components.searchFilters.takenFrom')}</span>
+            <input
+              type="date"
+              value={toDateInput(filters.takenRange?.start)}
+              onChange={(e) => handleTakenChange('start', e.target.value)}
+              className="flex-1 min-w-0 px-2 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+            />
+          </div>
+          <div className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+            <span>{t('components.searchFilters.takenTo')}</span>
+            <input
+              type="date"
+              value={toDateInput(filters.takenRange?.end)}
+              onChange={(e) => handleTakenChange('end', e.target.value)}
+              className="flex-1 min-w-0 px-2 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+            />
+          </div>
+          <input
+            type="text"
+            value={camera}
+            onChange={(e) => setCamera(e.target.value)}
+            onBlur={() => onFiltersChange({ ...filters, camera: camera.trim() || undefined })}
+            placeholder={t('components.searchFilters.cameraPlaceholder')}
+            className="w-full px-2 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+          />
+        </div>
+      </div>
+
+      {/* Directory Filter */}
+      <div>
+        <h4 className="text-sm font-medium text-gray-900 dark:text-white mb-2">{t('components.searchFilters.directories')}</h4>
+        <div className="space-y-2">
+          {/* 命中文件所属的索引目录，点击后只在该目录中搜索 */}
+          {facets?.directories.map((d) => (
+            <button
+              key={d.value}
+              onClick={() => onFiltersChange({ ...filters, includeDirs: [d.value] })}
+              title={d.value}
+              className="w-full flex items-center justify-between text-left text-sm text-gray-700 dark:text-gray-300 hover:text-primary-600"
+            >
+              <span className="truncate">{d.value}</span>
+              <span className="ml-2 text-gray-400">{d.count}</span>
+            </button>
+          ))}
+          <textarea
+            rows={2}
+            value={includeDirs}
+            onChange={(e) => setIncludeDirs(e.target.value)}
+            onBlur={() => handleDirsChange('includeDirs', includeDirs)}
+            placeholder={t('components.searchFilters.includeDirsPlaceholder')}
+            className="w-full px-2 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+          />
+          <textarea
+            rows={2}
+            value={excludeDirs}
+            onChange={(e) => setExcludeDirs(e.target.value)}
+            onBlur={() => handleDirsChange('excludeDirs', excludeDirs)}
+            placeholder={t('components.searchFilters.excludeDirsPlaceholder')}
+            className="w-full px-2 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+          />
+        </div>
+      </div>
+
       {/* Clear Filters */}
       <button
         onClick={() => onFiltersChange({})}
@@ -1,5 +1,5 @@
 import React, { useEffect, useState } from 'react';
-import { FileText, Calendar, Folder, ExternalLink } from 'lucide-react';
+import { FileText, Calendar, Folder, ExternalLink, Hash } from 'lucide-react';
 import { SearchResult } from '../types';
 import { useI18n } from '../i18n';
 import { openPath, revealItemInDir } from '@tauri-apps/plugin-opener';
@@ -8,12 +8,15 @@ interface SearchResultsProps {
   results: SearchResult[];
   isLoading: boolean;
   query: string;
+  // 正则搜索时只高亮后端返回的实际匹配
+  regex?: boolean;
 }
 
 export const SearchResults: React.FC<SearchResultsProps> = ({ 
   results, 
   isLoading, 
-  query 
+  query,
+  regex = false
 }) => {
   const { t, locale } = useI18n();
   const [menu, setMenu] = useState<{ x: number; y: number; visible: boolean; item?: SearchResult }>(() => ({ x: 0, y: 0, visible: false }));
@@ -56,10 +59,23 @@ export const SearchResults: React.FC<SearchResultsProps> = ({
     }
   };
 
-  const highlightText = (text: string, highlightQuery: string) => {
-    if (!highlightQuery) return text;
+  // 查询中要高亮的词：去掉运算符、排除的词、筛选操作符、字段名、引号、括号和前缀符号
+  const queryWords = (q: string) =>
+    q
+      .split(/\s+/)
+      .filter((w) => w && !w.startsWith('-') && !['AND', 'OR', 'NOT'].includes(w))
+      .filter((w) => !/^(type|modified|size|path):/i.test(w))
+      .map((w) => w.replace(/^[^:]*:/, '').replace(/^[+("]+|[)"*]+$/g, ''))
+      .filter(Boolean);
+  const escapeRegExp = (s: string) => s.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+
+  /* extraTerms 为后端返回的额外高亮词，如拼音查询匹配到的原始汉字 */
+  const highlightText = (text: string, highlightQuery: string, extraTerms: string[] = []) => {
+    const words = regex ? [] : queryWords(highlightQuery);
+    const patterns = [...words, ...extraTerms].filter(Boolean).map(escapeRegExp);
+    if (patterns.length === 0) return text;
     
-    const regex = new RegExp(`(${highlightQuery})`, 'gi');
+    const regex = new RegExp(`(${patterns.join('|')})`, 'gi');
     return text.replace(regex, '<mark class="bg-yellow-200 dark:bg-yellow-800">$1</mark>');
   };
 
@@ -82,7 +98,7 @@ export const SearchResults: React.FC<SearchResultsProps> = ({
     );
   }
 
-  if (!query) {
+  if (!query && results.length === 0) {
     return (
       <div className="text-center py-12">
         <FileText className="w-12 h-12 text-gray-400 mx-auto mb-4" />
@@ -135,7 +151,7 @@ export const SearchResults: React.FC<SearchResultsProps> = ({
                 <h3 
                   className="text-lg font-medium text-gray-900 dark:text-white truncate group-hover:text-primary-600 dark:group-hover:text-primary-400 transition-colors"
                   dangerouslySetInnerHTML={{
-                    __html: highlightText(result.title, query)
+                    __html: highlightText(result.title, query, result.highlightTerms)
                   }}
                 />
                 <div className="flex items-center space-x-2 ml-2">
@@ -155,7 +171,7 @@ export const SearchResults: React.FC<SearchResultsProps> = ({
               <p 
                 className="text-gray-600 dark:text-gray-300 mb-3 line-clamp-2 text-sm leading-relaxed"
                 dangerouslySetInnerHTML={{
-                  __html: highlightText(result.content, query)
+                  __html: highlightText(result.content, query, result.highlightTerms)
                 }}
               />
               
@@ -170,6 +186,25 @@ export const SearchResults: React.FC<SearchResultsProps> = ({
                   <Calendar className="w-3.5 h-3.5" />
                   <span>{formatDate(result.modifiedTime)}</span>
                 </div>
+                {result.section && (
+                  <div className="flex items-center space-x-1.5">
+                    <Hash className="w-3.5 h-3.5" />
+                    <span className="truncate max-w-xs">{result.section}</span>
+                  </div>
+                )}
+                {result.line != null && (
+                  <span>{t('components.searchResults.line', { line: result.line })}</span>
+                )}
+                {result.row != null && (
+                  <span>{t('components.searchResults.row', { row: result.row })}</span>
+                )}
+                {result.passage && result.passage.kind !== 'lines' && (
+                  <span>
+                    {result.passage.kind === 'sheet' && result.passage.name
+                      ? t('components.searchResults.passage.sheet', { name: result.passage.name })
+                      : t(`components.searchResults.passage.${result.passage.kind}`, { index: result.passage.index })}
+                  </span>
+                )}
               </div>
               
               {result.highlights && result.highlights.length > 0 && (
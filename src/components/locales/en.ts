@@ -7,19 +7,28 @@ export default {
   searchFilters: {
     fileType: 'File Type',
     types: {
-      txt: 'Text (.txt)',
-      md: 'Markdown (.md)',
-      pdf: 'PDF (.pdf)',
-      doc: 'Word (.doc, .docx)',
-      xls: 'Excel (.xls, .xlsx)',
-      ppt: 'PowerPoint (.ppt, .pptx)',
-      plain: 'Other Plain Text (.js, .json, ...)',
+      documents: 'Documents',
+      spreadsheets: 'Spreadsheets',
+      presentations: 'Presentations',
+      code: 'Code',
+      images: 'Images',
+      audio: 'Audio',
+      video: 'Video',
+      archives: 'Archives',
+      logs: 'Logs',
     },
     modifiedTime: 'Modified Time',
     any: 'Any',
-    lastDay: 'Last Day',
-    lastWeek: 'Last Week',
-    lastMonth: 'Last Month',
+    today: 'Today',
+    thisWeek: 'This Week',
+    thisMonth: 'This Month',
+    directories: 'Directories',
+    includeDirsPlaceholder: 'Only search in these directories (one per line, e.g. D:\\Contracts\\2024)',
+    excludeDirsPlaceholder: 'Exclude these directories (one per line, e.g. archive/)',
+    photos: 'Photos',
+    takenFrom: 'Taken from',
+    takenTo: 'to',
+    cameraPlaceholder: 'Camera (e.g. Canon EOS R5)',
     clear: 'Clear All Filters',
   },
   searchHistory: {
@@ -35,5 +44,12 @@ export default {
     daysAgo: '{days} days ago',
     revealInFolder: 'Reveal in File Manager',
     openWithSystem: 'Open with System',
+    line: 'Line {line}',
+    row: 'Row {row}',
+    passage: {
+      slide: 'Slide {index}',
+      sheet: 'Sheet {name}',
+      text: 'Passage {index}',
+    },
   },
 };
@@ -7,19 +7,28 @@ export default {
   searchFilters: {
     fileType: '文件类型',
     types: {
-      txt: '文本文件 (.txt)',
-      md: 'Markdown (.md)',
-      pdf: 'PDF文档 (.pdf)',
-      doc: 'Word文档 (.doc, .docx)',
-      xls: 'Excel表格 (.xls, .xlsx)',
-      ppt: 'PPT演示文稿 (.ppt, .pptx)',
-      plain: '其他文本文件(.js, .json, ...)',
+      documents: '文档',
+      spreadsheets: '表格',
+      presentations: '演示文稿',
+      code: '代码',
+      images: '图片',
+      audio: '音频',
+      video: '视频',
+      archives: '压缩包',
+      logs: '日志',
     },
     modifiedTime: '修改时间',
     any: '不限',
-    lastDay: '最近一天',
-    lastWeek: '最近一周',
-    lastMonth: '最近一月',
+    today: '今天',
+    thisWeek: '本周',
+    thisMonth: '本月',
+    directories: '目录',
+    includeDirsPlaceholder: '只搜索这些目录（每行一个，如 D:\\合同\\2024）',
+    excludeDirsPlaceholder: '排除这些目录（每行一个，如 archive/）',
+    photos: '照片',
+    takenFrom: '拍摄日期从',
+    takenTo: '至',
+    cameraPlaceholder: '相机（如 Canon EOS R5）',
     clear: '清除所有筛选',
   },
   searchHistory: {
@@ -35,5 +44,12 @@ export default {
     daysAgo: '{days}天前',
     revealInFolder: '在文件夹中显示',
     openWithSystem: '使用系统程序打开',
+    line: '第 {line} 行',
+    row: '第 {row} 行记录',
+    passage: {
+      slide: '第 {index} 张幻灯片',
+      sheet: '工作表 {name}',
+      text: '第 {index} 段',
+    },
   },
 };
@@ -19,6 +19,8 @@ export const IndexManagementPage: React.FC = () => {
     indexSize: 0,
     lastUpdated: 0
   });
+  const [indexError, setIndexError] = useState<string | null>(null);
+  const [filenameIndexError, setFilenameIndexError] = useState<string | null>(null);
 
   useEffect(() => {
     (async () => {
@@ -64,8 +66,16 @@ export const IndexManagementPage: React.FC = () => {
 
   /* 手动触发索引重建 */
   const handleRebuildIndex = async () => {
+    setIndexError(null);
     const unlisten = await listen('index-progress', async (event) => {
       const payload = event.payload as any;
+      // 重建失败时仍使用原索引，保留原来的索引状态
+      if (payload.error) {
+        setIndexError(payload.error);
+        setIndexStatus(prev => ({ ...prev, isIndexing: false }));
+        unlisten();
+        return;
+      }
       setIndexStatus({
         isIndexing: !!payload.isIndexing,
         progress: Number(payload.progress ?? 0),
@@ -86,7 +96,23 @@ export const IndexManagementPage: React.FC = () => {
         unlisten();
       }
     });
-    await invoke('rebuild_index', { directories });
+    setFilenameIndexError(null);
+    const unlistenFilename = await listen('filename-index-progress', (event) => {
+      const payload = event.payload as any;
+      if (!payload.isIndexing) {
+        setFilenameIndexError(payload.error ?? null);
+        unlistenFilename();
+      }
+    });
+    // 文件名索引只遍历目录、不提取内容，很快完成，覆盖内容索引不支持的文件类型；上次重建尚未完成时不再重建
+    await invoke('rebuild_filename_index', { directories }).catch((e) => {
+      setFilenameIndexError(String(e));
+      unlistenFilename();
+    });
+    await invoke('rebuild_index', { directories }).catch((e) => {
+      setIndexError(String(e));
+      unlisten();
+    });
   };
 
   /* 将文件大小变为可读格式 */
@@ -151,6 +177,18 @@ export const IndexManagementPage: React.FC = () => {
             </div>
           )}
 
+          {indexError && (
+            <div className="mb-4 text-sm text-red-600 dark:text-red-400">
+              {t('pages.indexManagement.indexError', { error: indexError })}
+            </div>
+          )}
+
+          {filenameIndexError && (
+            <div className="mb-4 text-sm text-red-600 dark:text-red-400">
+              {t('pages.indexManagement.filenameIndexError', { error: filenameIndexError })}
+            </div>
+          )}
+
           <div className="grid grid-cols-2 md:grid-cols-4 gap-4 text-sm">
             <div>
               <div className="text-gray-500 dark:text-gray-400">{t('pages.indexManagement.totalFiles')}</div>
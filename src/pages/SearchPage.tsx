@@ -1,11 +1,12 @@
 import React, { useState, useCallback, useEffect } from "react";
 import { Search, Filter, Clock } from "lucide-react";
-import { SearchResult, SearchFilters, SearchResponse } from "../types";
+import { SearchResult, SearchFilters, SearchResponse, SearchSort, SearchFacets, SearchError, FilenameSearchResponse } from "../types";
 import { SearchResults } from "../components/SearchResults";
 import { SearchFiltersComponent } from "../components/SearchFilters";
 import { SearchHistory } from "../components/SearchHistory";
 import { useI18n } from "../i18n";
 import { invoke } from "@tauri-apps/api/core";
+import { loadSettings } from "../utils/settingsStorage";
 
 export const SearchPage: React.FC = () => {
   const { t } = useI18n();
@@ -13,48 +14,110 @@ export const SearchPage: React.FC = () => {
   const [isSearching, setIsSearching] = useState(false);
   const [searchResults, setSearchResults] = useState<SearchResult[]>([]);
   const [totalResults, setTotalResults] = useState(0);
+  // 命中文件过多时后端只返回排名最前的部分，之后的页为空
+  const [truncated, setTruncated] = useState(false);
   const [searchTime, setSearchTime] = useState(0);
   const [showFilters, setShowFilters] = useState(true);
   const [filters, setFilters] = useState<SearchFilters>({});
   const [searchHistory, setSearchHistory] = useState<string[]>([]);
   const [currentPage, setCurrentPage] = useState(1);
   const [perPage, setPerPage] = useState(20);
+  const [sort, setSort] = useState<SearchSort>("relevance");
+  const [facets, setFacets] = useState<SearchFacets | null>(null);
+  // 设置中的匹配精度，n-gram 等切分出的词项至少命中的比例
+  const [matchPrecision, setMatchPrecision] = useState(1);
+  // 模糊搜索：开启后英文等单词允许按设置的编辑距离存在拼写错误
+  const [fuzzy, setFuzzy] = useState(false);
+  const [fuzzyDistance, setFuzzyDistance] = useState(1);
+  // 正则搜索：查询整体作为正则表达式，按实际匹配高亮
+  const [regex, setRegex] = useState(false);
+  // 仅搜索文件名：查询轻量的文件名索引，输入时即时搜索
+  const [filenameOnly, setFilenameOnly] = useState(false);
+  useEffect(() => {
+    loadSettings().then((s) => {
+      if (s?.search?.matchPrecision != null) setMatchPrecision(s.search.matchPrecision);
+      if (s?.search?.fuzzyDistance != null) setFuzzyDistance(s.search.fuzzyDistance);
+    });
+  }, []);
+  // 查询语法错误（如无法识别的日期、大小）
+  const [searchError, setSearchError] = useState<string | null>(null);
+  // 有查询，或按时间、大小、名称排序浏览文件
+  const browsing = Boolean(query.trim()) || (sort !== "relevance" && !filenameOnly);
+
+  // 文件名索引覆盖所有类型的文件，结果转为与全文搜索相同的结构
+  const searchFilenames = async (q: string, limit: number, offset: number): Promise<SearchResponse> => {
+    const resp = await invoke<FilenameSearchResponse>("search_filenames", { query: q, limit, offset });
+    return {
+      results: resp.results.map((r) => ({
+        id: r.filePath,
+        title: r.name,
+        content: "",
+        filePath: r.filePath,
+        fileType: r.fileType,
+        modifiedTime: r.modifiedTime,
+        score: 1,
+        highlights: [],
+      })),
+      totalCount: resp.totalCount,
+      searchTime: 0,
+      hasMore: false,
+      facets: null,
+    };
+  };
 
   const performSearch = useCallback(
-    async (searchQuery: string) => {
-      if (!searchQuery.trim()) {
+    async (searchQuery: string, page: number = currentPage) => {
+      // 按时间、大小或名称排序时允许空查询，可用于浏览最近的文件；文件名模式不支持排序和筛选
+      if (!searchQuery.trim() && (sort === "relevance" || filenameOnly)) {
         setSearchResults([]);
+        setFacets(null);
+        setSearchError(null);
         return;
       }
 
       setIsSearching(true);
       const t0 = performance.now(); // 搜索开始时间
       try {
-        const resp = await invoke<SearchResponse>("search_index", {
-          query: searchQuery,
-          limit: perPage,
-          offset: (currentPage - 1) * perPage,
-          filters,
-        });
+        const resp = filenameOnly
+          ? await searchFilenames(searchQuery, perPage, (page - 1) * perPage)
+          : await invoke<SearchResponse>("search_index", {
+            query: searchQuery,
+            limit: perPage,
+            offset: (page - 1) * perPage,
+            filters,
+            sort,
+            facets: true,
+            matchPrecision,
+            fuzzy: fuzzy ? fuzzyDistance : 0,
+            regex,
+          });
         setSearchResults(resp.results);
+        setFacets(resp.facets ?? null);
+        setSearchError(null);
         setTotalResults(resp.totalCount || resp.results.length);
+        setTruncated(Boolean(resp.truncated));
         const t1 = performance.now();
         setSearchTime(Number(((t1 - t0) / 1000).toFixed(3))); // 搜索耗时（秒）
       } catch (e) {
         console.error("Search error:", e);
+        setSearchError((e as SearchError)?.message ?? String(e));
         setSearchResults([]);
         setTotalResults(0);
+        setTruncated(false);
         setSearchTime(0);
+        setFacets(null);
       }
 
-      // 更新搜索历史
-      setSearchHistory((prev) =>
-        prev.includes(searchQuery) ? prev : [searchQuery, ...prev.slice(0, 9)]
-      );
+      // 更新搜索历史，文件名模式随输入即时搜索，不记录
+      if (searchQuery.trim() && !filenameOnly) {
+        setSearchHistory((prev) =>
+          prev.includes(searchQuery) ? prev : [searchQuery, ...prev.slice(0, 9)]
+        );
+      }
 
       setIsSearching(false);
     },
-    [filters, currentPage, perPage]
+    [filters, currentPage, perPage, sort, matchPrecision, fuzzy, fuzzyDistance, regex, filenameOnly]
   );
 
   const handleSearchHistoryClick = (historyQuery: string) => {
@@ -68,10 +131,24 @@ export const SearchPage: React.FC = () => {
   };
 
   useEffect(() => {
-    if (query.trim() && !isSearching) {
+    if (!browsing) {
+      // 空查询切回相关度排序时清空上一次浏览的结果
+      setSearchResults([]);
+      setTotalResults(0);
+      setFacets(null);
+      setSearchError(null);
+    } else if (!isSearching) {
       performSearch(query);
     }
-  }, [filters, currentPage, perPage]);
+  }, [filters, currentPage, perPage, sort, fuzzy, regex]);
+
+  useEffect(() => {
+    if (filenameOnly) {
+      // setCurrentPage 要到下次渲染才生效，这里直接按第一页搜索
+      setCurrentPage(1);
+      performSearch(query, 1);
+    }
+  }, [query, filenameOnly]);
 
   return (
     <div className="h-full flex flex-col">
@@ -143,16 +220,20 @@ export const SearchPage: React.FC = () => {
                   />
                 </div>
 
-                <div>
-                  <h3 className="text-sm font-medium text-gray-900 dark:text-white mb-3 flex items-center">
-                    <Filter className="w-4 h-4 mr-2" />
-                    {t("pages.searchPage.filters")}
-                  </h3>
-                  <SearchFiltersComponent
-                    filters={filters}
-                    onFiltersChange={(newFilters) => {setFilters(newFilters); setCurrentPage(1);}}
-                  />
-                </div>
+                {/* 文件名索引只按名称和路径匹配，不支持筛选 */}
+                {!filenameOnly && (
+                  <div>
+                    <h3 className="text-sm font-medium text-gray-900 dark:text-white mb-3 flex items-center">
+                      <Filter className="w-4 h-4 mr-2" />
+                      {t("pages.searchPage.filters")}
+                    </h3>
+                    <SearchFiltersComponent
+                      filters={filters}
+                      facets={facets}
+                      onFiltersChange={(newFilters) => {setFilters(newFilters); setCurrentPage(1);}}
+                    />
+                  </div>
+                )}
               </>
             )}
           </div>
@@ -162,34 +243,81 @@ export const SearchPage: React.FC = () => {
         <div className="flex-1 overflow-hidden">
           <div className="h-full overflow-y-auto">
             <div className="max-w-4xl mx-auto p-6">
-              {/* Search Stats */}
-              {query && (
-                <div className="mb-4 flex items-center justify-between text-sm text-gray-600 dark:text-gray-400">
-                  <div>
-                    {isSearching ? (
-                      <span>{t("pages.searchPage.searching")}</span>
-                    ) : (
-                      <span>
-                        {t("pages.searchPage.stats", {
-                          count: totalResults,
-                          time: searchTime,
-                        })}
-                      </span>
-                    )}
-                  </div>
-                  <div className="flex items-center space-x-2">
-                    <span className="text-gray-600 dark:text-gray-400">{t('pages.common.perPage') || '每页'}</span>
-                    <select
-                      value={perPage}
-                      onChange={(e) => { setPerPage(parseInt(e.target.value)); setCurrentPage(1); }}
-                      className="px-2 py-1 border rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
-                    >
-                      <option value={10}>10</option>
-                      <option value={20}>20</option>
-                      <option value={50}>50</option>
-                      <option value={100}>100</option>
-                    </select>
-                  </div>
+              {/* Search Stats，排序方式始终可选，未输入查询时也可按时间、大小或名称浏览文件 */}
+              <div className="mb-4 flex items-center justify-between text-sm text-gray-600 dark:text-gray-400">
+                <div>
+                  {browsing && (isSearching ? (
+                    <span>{t("pages.searchPage.searching")}</span>
+                  ) : (
+                    <span>
+                      {t("pages.searchPage.stats", {
+                        count: totalResults,
+                        time: searchTime,
+                      })}
+                      {truncated && ` ${t("pages.searchPage.truncated")}`}
+                    </span>
+                  ))}
+                </div>
+                <div className="flex items-center space-x-2">
+                  <label className="flex items-center" title={t("pages.searchPage.fuzzyHint")}>
+                    <input
+                      type="checkbox"
+                      checked={fuzzy}
+                      onChange={(e) => { setFuzzy(e.target.checked); setCurrentPage(1); }}
+                      className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                    />
+                    <span className="ml-1 text-gray-600 dark:text-gray-400">{t("pages.searchPage.fuzzy")}</span>
+                  </label>
+                  <label className="flex items-center" title={t("pages.searchPage.regexHint")}>
+                    <input
+                      type="checkbox"
+                      checked={regex}
+                      onChange={(e) => { setRegex(e.target.checked); setCurrentPage(1); }}
+                      className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                    />
+                    <span className="ml-1 text-gray-600 dark:text-gray-400">{t("pages.searchPage.regex")}</span>
+                  </label>
+                  <label className="flex items-center" title={t("pages.searchPage.filenameOnlyHint")}>
+                    <input
+                      type="checkbox"
+                      checked={filenameOnly}
+                      onChange={(e) => setFilenameOnly(e.target.checked)}
+                      className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                    />
+                    <span className="ml-1 text-gray-600 dark:text-gray-400">{t("pages.searchPage.filenameOnly")}</span>
+                  </label>
+                  {/* 文件名结果按匹配程度排序，不提供排序方式 */}
+                  {!filenameOnly && (
+                    <>
+                      <span className="text-gray-600 dark:text-gray-400">{t("pages.searchPage.sortBy")}</span>
+                      <select
+                        value={sort}
+                        onChange={(e) => { setSort(e.target.value as SearchSort); setCurrentPage(1); }}
+                        className="px-2 py-1 border rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                      >
+                        {(["relevance", "newest", "oldest", "largest", "smallest", "name"] as SearchSort[]).map((s) => (
+                          <option key={s} value={s}>{t(`pages.searchPage.sort.${s}`)}</option>
+                        ))}
+                      </select>
+                    </>
+                  )}
+                  <span className="text-gray-600 dark:text-gray-400">{t('pages.common.perPage') || '每页'}</span>
+                  <select
+                    value={perPage}
+                    onChange={(e) => { setPerPage(parseInt(e.target.value)); setCurrentPage(1); }}
+                    className="px-2 py-1 border rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                  >
+                    <option value={10}>10</option>
+                    <option value={20}>20</option>
+                    <option value={50}>50</option>
+                    <option value={100}>100</option>
+                  </select>
+                </div>
+              </div>
+
+              {searchError && (
+                <div className="mb-4 px-4 py-2 text-sm text-red-700 dark:text-red-300 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded-lg">
+                  {searchError}
                 </div>
               )}
 
@@ -198,8 +326,9 @@ export const SearchPage: React.FC = () => {
                 results={searchResults}
                 isLoading={isSearching}
                 query={query}
+                regex={regex}
               />
-              {query && (
+              {browsing && (
                 <div className="mt-4 flex items-center justify-end space-x-2">
                   <button
                     onClick={() => gotoPage(currentPage - 1)}
@@ -213,7 +342,7 @@ export const SearchPage: React.FC = () => {
                   </span>
                   <button
                     onClick={() => gotoPage(currentPage + 1)}
-                    disabled={isSearching || currentPage * perPage >= totalResults}
+                    disabled={isSearching || currentPage * perPage >= totalResults || (truncated && searchResults.length < perPage)}
                     className="px-3 py-1 border rounded disabled:opacity-50"
                   >
                     {t("pages.common.next") || "下一页"}
@@ -4,22 +4,37 @@ import { AppSettings } from '../types';
 import { useI18n } from '../i18n';
 import { useTheme } from '../hooks/useTheme';
 import { saveSettings, loadSettings } from '../utils/settingsStorage';
+import { loadDirectories } from '../utils/directoriesStorage';
 import { invoke } from '@tauri-apps/api/core';
 
+/* 后端支持词干提取的文本语言 */
+const TEXT_LANGUAGES = ['english', 'french', 'german', 'spanish', 'italian', 'portuguese', 'dutch', 'swedish', 'russian'];
+
 export const SettingsPage: React.FC = () => {
   const { t, lang, setLang, locale } = useI18n();
   const { setTheme } = useTheme();
   const [settings, setSettings] = useState<AppSettings>({
     search: {
       resultsPerPage: 20,
-      matchPrecision: 0.8,
+      matchPrecision: 1,
+      fuzzyDistance: 1,
       enableHighlighting: true,
+      defaultOperator: 'or',
     },
     indexing: {
       autoUpdate: true,
       updateInterval: 3600, // 1 hour
       excludePatterns: ['*.tmp', '*.log', 'node_modules/*'],
       maxFileSize: 50 * 1024 * 1024, // 50MB
+      lineChunking: false,
+      passageSize: 0,
+      tokenizer: 'ngram',
+      userDictionary: '',
+      pinyin: false,
+      pinyinContent: false,
+      fuzzyContent: false,
+      regexIndex: true,
+      textLanguage: '',
     },
     ui: {
       theme: 'system',
@@ -30,6 +45,8 @@ export const SettingsPage: React.FC = () => {
 
   const [showExcludeInput, setShowExcludeInput] = useState(false);
   const [newExcludePattern, setNewExcludePattern] = useState('');
+  const [userDictionary, setUserDictionary] = useState('');
+  const [rebuildRequired, setRebuildRequired] = useState(false);
 
   const handleSettingChange = async (section: keyof AppSettings, key: string, value: any) => {
     // If theme is changed, update it immediately using useTheme hook
@@ -53,18 +70,47 @@ export const SettingsPage: React.FC = () => {
     });
   };
 
+  /* 分词方式、用户词典、拼音索引、模糊搜索内容单词、正则原文词项索引、文本语言、按行分块或段落拆分变化后索引中的词项或文档不再适用，保存设置后自动重建索引 */
+  const handleTokenizerChange = async (
+    key: 'tokenizer' | 'userDictionary' | 'pinyin' | 'pinyinContent' | 'fuzzyContent' | 'regexIndex' | 'textLanguage' | 'lineChunking' | 'passageSize',
+    value: string | boolean | number,
+  ) => {
+    if ((settings.indexing[key] ?? '') === value) return;
+    const next = { ...settings, indexing: { ...settings.indexing, [key]: value } };
+    setSettings(next);
+    await saveSettings(next);
+    const directories = await loadDirectories();
+    if (directories && directories.length > 0) {
+      // 上次重建尚未完成时后端拒绝重建，提示用户完成后在索引管理页手动重建
+      await invoke('rebuild_index', { directories })
+        .then(() => setRebuildRequired(false))
+        .catch(() => setRebuildRequired(true));
+    }
+  };
+
   const handleResetSettings = () => {
     setSettings({
       search: {
         resultsPerPage: 20,
-        matchPrecision: 0.8,
+        matchPrecision: 1,
+        fuzzyDistance: 1,
         enableHighlighting: true,
+        defaultOperator: 'or',
       },
       indexing: {
         autoUpdate: true,
         updateInterval: 3600,
         excludePatterns: ['*.tmp', '*.log', 'node_modules/*'],
         maxFileSize: 50 * 1024 * 1024,
+        lineChunking: false,
+        passageSize: 0,
+        tokenizer: 'ngram',
+        userDictionary: '',
+        pinyin: false,
+        pinyinContent: false,
+        fuzzyContent: false,
+        regexIndex: true,
+        textLanguage: '',
       },
       ui: {
         theme: 'system',
@@ -102,6 +148,7 @@ export const SettingsPage: React.FC = () => {
       const loaded = await loadSettings();
       if (loaded) {
         setSettings(loaded);
+        setUserDictionary(loaded.indexing?.userDictionary ?? '');
         if (loaded.ui?.language) setLang(loaded.ui.language as any);
       }
     })();
@@ -160,8 +207,35 @@ export const SettingsPage: React.FC = () => {
                 <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">
                   {(settings.search.matchPrecision * 100).toFixed(0)}%
                 </div>
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.matchPrecisionHint')}</div>
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">{t('pages.settings.fuzzyDistance')}</label>
+                <select
+                  value={settings.search.fuzzyDistance ?? 1}
+                  onChange={(e) => handleSettingChange('search', 'fuzzyDistance', parseInt(e.target.value))}
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                >
+                  <option value={1}>1</option>
+                  <option value={2}>2</option>
+                </select>
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.fuzzyDistanceHint')}</div>
               </div>
               
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">{t('pages.settings.defaultOperator')}</label>
+                <select
+                  value={settings.search.defaultOperator ?? 'or'}
+                  onChange={(e) => handleSettingChange('search', 'defaultOperator', e.target.value)}
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                >
+                  <option value="and">{t('pages.settings.defaultOperatorAnd')}</option>
+                  <option value="or">{t('pages.settings.defaultOperatorOr')}</option>
+                </select>
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.querySyntaxHint')}</div>
+              </div>
+
               <label className="flex items-center">
                 <input
                   type="checkbox"
@@ -180,6 +254,10 @@ export const SettingsPage: React.FC = () => {
               <Database className="w-5 h-5 text-primary-600 mr-2" />
               <h2 className="text-lg font-semibold text-gray-900 dark:text-white">{t('pages.settings.indexing')}</h2>
             </div>
+
+            {rebuildRequired && (
+              <div className="mb-4 text-sm text-amber-600 dark:text-amber-400">{t('pages.settings.rebuildRequired')}</div>
+            )}
             
             <div className="space-y-4">
               <label className="flex items-center">
@@ -218,6 +296,117 @@ export const SettingsPage: React.FC = () => {
                 />
               </div>
               
+              <label className="flex items-center">
+                <input
+                  type="checkbox"
+                  checked={settings.indexing.lineChunking ?? false}
+                  onChange={(e) => handleTokenizerChange('lineChunking', e.target.checked)}
+                  className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                />
+                <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">{t('pages.settings.lineChunking')}</span>
+              </label>
+
+              <label className="flex items-center">
+                <input
+                  type="checkbox"
+                  checked={(settings.indexing.passageSize ?? 0) > 0}
+                  onChange={(e) => handleTokenizerChange('passageSize', e.target.checked ? 3000 : 0)}
+                  className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                />
+                <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">{t('pages.settings.passageSplitting')}</span>
+              </label>
+
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">{t('pages.settings.tokenizer')}</label>
+                <select
+                  value={settings.indexing.tokenizer ?? 'ngram'}
+                  onChange={(e) => handleTokenizerChange('tokenizer', e.target.value)}
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                >
+                  <option value="ngram">{t('pages.settings.tokenizerNgram')}</option>
+                  <option value="jieba">{t('pages.settings.tokenizerJieba')}</option>
+                </select>
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.tokenizerHint')}</div>
+              </div>
+
+              {settings.indexing.tokenizer === 'jieba' && (
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">{t('pages.settings.userDictionary')}</label>
+                  <input
+                    value={userDictionary}
+                    onChange={(e) => setUserDictionary(e.target.value)}
+                    onBlur={() => handleTokenizerChange('userDictionary', userDictionary.trim())}
+                    placeholder={t('pages.settings.userDictionaryPlaceholder')}
+                    className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                  />
+                </div>
+              )}
+
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">{t('pages.settings.textLanguage')}</label>
+                <select
+                  value={settings.indexing.textLanguage ?? ''}
+                  onChange={(e) => handleTokenizerChange('textLanguage', e.target.value)}
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                >
+                  <option value="">{t('pages.settings.textLanguageOff')}</option>
+                  {TEXT_LANGUAGES.map((lang) => (
+                    <option key={lang} value={lang}>{t(`pages.settings.textLanguages.${lang}`)}</option>
+                  ))}
+                </select>
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.textLanguageHint')}</div>
+              </div>
+
+              <div>
+                <label className="flex items-center">
+                  <input
+                    type="checkbox"
+                    checked={settings.indexing.pinyin ?? false}
+                    onChange={(e) => handleTokenizerChange('pinyin', e.target.checked)}
+                    className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                  />
+                  <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">{t('pages.settings.pinyin')}</span>
+                </label>
+                {settings.indexing.pinyin && (
+                  <label className="flex items-center mt-2 ml-6">
+                    <input
+                      type="checkbox"
+                      checked={settings.indexing.pinyinContent ?? false}
+                      onChange={(e) => handleTokenizerChange('pinyinContent', e.target.checked)}
+                      className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                    />
+                    <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">{t('pages.settings.pinyinContent')}</span>
+                  </label>
+                )}
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.pinyinHint')}</div>
+              </div>
+
+              <div>
+                <label className="flex items-center">
+                  <input
+                    type="checkbox"
+                    checked={settings.indexing.fuzzyContent ?? false}
+                    onChange={(e) => handleTokenizerChange('fuzzyContent', e.target.checked)}
+                    className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                  />
+                  <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">{t('pages.settings.fuzzyContent')}</span>
+                </label>
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.fuzzyContentHint')}</div>
+              </div>
+
+              <div>
+                <label className="flex items-center">
+                  <input
+                    type="checkbox"
+                    checked={settings.indexing.regexIndex ?? true}
+                    onChange={(e) => handleTokenizerChange('regexIndex', e.target.checked)}
+                    className="rounded border-gray-300 text-primary-600 focus:ring-primary-500"
+                  />
+                  <span className="ml-2 text-sm text-gray-700 dark:text-gray-300">{t('pages.settings.regexIndex')}</span>
+                </label>
+                <div className="text-sm text-gray-500 dark:text-gray-400 mt-1">{t('pages.settings.regexIndexHint')}</div>
+              </div>
+
               <div>
                 <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">{t('pages.settings.excludePatterns')}</label>
                 <div className="space-y-2">
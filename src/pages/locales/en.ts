@@ -1,11 +1,27 @@
 export default {
   searchPage: {
     title: 'Search',
-    placeholder: 'Enter keywords...',
+    placeholder: 'Enter keywords, e.g. budget type:xlsx modified:>2024-06-01 size:<5MB path:contracts',
     history: 'Search History',
     filters: 'Filters',
     searching: 'Searching...',
     stats: '{count} results found in {time} seconds',
+    truncated: '(only the top-ranked files can be paged through)',
+    sortBy: 'Sort',
+    fuzzy: 'Fuzzy',
+    fuzzyHint: 'Tolerate typos in English and other Latin-script words; Chinese still matches exactly',
+    regex: 'Regex',
+    filenameOnly: 'Names only',
+    filenameOnlyHint: 'Search file names and paths of all files as you type, including types without content indexing',
+    regexHint: 'Treat the query as a regular expression, e.g. ERR-\\d{4} or user_id=\\w+; case-sensitive, prefix with (?i) to ignore case',
+    sort: {
+      relevance: 'Relevance',
+      newest: 'Newest',
+      oldest: 'Oldest',
+      largest: 'Largest',
+      smallest: 'Smallest',
+      name: 'Name A–Z',
+    },
   },
   indexManagement: {
     title: 'Index Management',
@@ -19,6 +35,8 @@ export default {
     indexSize: 'Index Size',
     lastUpdated: 'Last Updated',
     never: 'Never',
+    indexError: 'Index rebuild failed: {error}',
+    filenameIndexError: 'Filename index rebuild failed: {error}',
     rebuild: 'Rebuild Index',
     directories: 'Indexed Directories',
     addDir: 'Add Directory',
@@ -37,12 +55,49 @@ export default {
     search: 'Search',
     resultsPerPage: 'Results Per Page',
     matchPrecision: 'Match Precision',
+    matchPrecisionHint: 'Share of query fragments (n-grams) that must match; lower values tolerate partial matches',
+    fuzzyDistance: 'Fuzzy Search Edit Distance',
+    fuzzyDistanceHint: 'Maximum number of typo characters allowed per word in fuzzy search; words of 5 letters or fewer allow at most 1',
+    defaultOperator: 'Default Operator',
+    defaultOperatorAnd: 'AND (all words must match)',
+    defaultOperatorOr: 'OR (any word matches)',
+    querySyntaxHint: 'Syntax: AND / OR / NOT, -exclude, "exact phrase", prefix*, (grouping)',
     enableHighlight: 'Enable Result Highlighting',
     indexing: 'Indexing',
     autoUpdate: 'Enable Auto Update',
     updateInterval: 'Update Interval (seconds)',
     intervalHint: 'Recommended 3600 seconds (1 hour)',
     maxFileSize: 'Max File Size (MB)',
+    lineChunking: 'Index large log/text files in line chunks (show line numbers)',
+    passageSplitting: 'Split long documents into passages (show the matching passage)',
+    tokenizer: 'Chinese Tokenizer',
+    tokenizerNgram: 'N-gram (default, higher recall, larger index)',
+    tokenizerJieba: 'Dictionary segmentation (jieba, more precise)',
+    tokenizerHint: 'Switching the tokenizer rebuilds the index automatically',
+    rebuildRequired: 'An index rebuild is already running, so these changes were not applied. Rebuild the index from Index Management once it finishes',
+    userDictionary: 'User Dictionary',
+    userDictionaryPlaceholder: 'Path to a user dictionary file (one per line: word [freq] [tag])',
+    pinyin: 'Pinyin search (file names)',
+    pinyinContent: 'Also index content as pinyin (noticeably larger index)',
+    pinyinHint: 'Search with full pinyin or initials, e.g. luyouqi or lyq matches “路由器”; changing this rebuilds the index',
+    fuzzyContent: 'Fuzzy search also matches words in content (larger index)',
+    fuzzyContentHint: 'Without it, fuzzy search only tolerates typos in file names, titles and code symbols. Changing this rebuilds the index',
+    regexIndex: 'Index raw words for regex search (larger index)',
+    regexIndexHint: 'Narrows regex search to candidate documents; regex search is unavailable without it. Changing this rebuilds the index',
+    textLanguage: 'Text Language',
+    textLanguageOff: 'None (n-gram tokenization)',
+    textLanguageHint: 'Tokenizes English and other languages by word, removing stop words and accents and stemming (e.g. configuration matches configure); changing this rebuilds the index',
+    textLanguages: {
+      english: 'English',
+      french: 'French',
+      german: 'German',
+      spanish: 'Spanish',
+      italian: 'Italian',
+      portuguese: 'Portuguese',
+      dutch: 'Dutch',
+      swedish: 'Swedish',
+      russian: 'Russian',
+    },
     excludePatterns: 'Exclude Patterns',
     delete: 'Delete',
     addExclude: 'Add Exclude Pattern',
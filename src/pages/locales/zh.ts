@@ -1,11 +1,27 @@
 export default {
   searchPage: {
     title: '全文搜索',
-    placeholder: '输入搜索关键词...',
+    placeholder: '输入搜索关键词，如 预算 type:xlsx modified:>2024-06-01 size:<5MB path:合同',
     history: '搜索历史',
     filters: '筛选条件',
     searching: '搜索中...',
     stats: '找到 {count} 个结果，用时 {time} 秒',
+    truncated: '（只能翻阅排名最前的部分文件）',
+    sortBy: '排序',
+    fuzzy: '模糊',
+    fuzzyHint: '英文等拉丁字母单词允许拼写错误，中文仍精确匹配',
+    regex: '正则',
+    filenameOnly: '仅文件名',
+    filenameOnlyHint: '输入时即时搜索所有文件（包括不支持内容索引的类型）的文件名和路径',
+    regexHint: '将查询作为正则表达式，如 ERR-\\d{4}、user_id=\\w+；区分大小写，以 (?i) 开头可忽略大小写',
+    sort: {
+      relevance: '相关度',
+      newest: '最新修改',
+      oldest: '最早修改',
+      largest: '文件最大',
+      smallest: '文件最小',
+      name: '名称 A–Z',
+    },
   },
   indexManagement: {
     title: '索引管理',
@@ -19,6 +35,8 @@ export default {
     indexSize: '索引大小',
     lastUpdated: '最后更新',
     never: '从未',
+    indexError: '索引重建失败：{error}',
+    filenameIndexError: '文件名索引重建失败：{error}',
     rebuild: '重建索引',
     directories: '索引目录',
     addDir: '添加目录',
@@ -37,12 +55,49 @@ export default {
     search: '搜索设置',
     resultsPerPage: '每页结果数',
     matchPrecision: '匹配精度',
+    matchPrecisionHint: '查询词切分出的片段（n-gram）至少命中的比例，较低时允许部分匹配',
+    fuzzyDistance: '模糊搜索编辑距离',
+    fuzzyDistanceHint: '模糊搜索时每个单词最多允许的拼写错误字符数，5 个字母及以下的单词最多允许 1 个',
+    defaultOperator: '默认运算',
+    defaultOperatorAnd: 'AND（所有词都必须命中）',
+    defaultOperatorOr: 'OR（命中任意一个词）',
+    querySyntaxHint: '语法：AND / OR / NOT、-排除、"精确短语"、前缀*、(分组)',
     enableHighlight: '启用搜索结果高亮显示',
     indexing: '索引设置',
     autoUpdate: '启用自动更新索引',
     updateInterval: '更新间隔 (秒)',
     intervalHint: '建议设置为 3600 秒 (1小时)',
     maxFileSize: '最大文件大小 (MB)',
+    lineChunking: '大日志/文本文件按行分块索引（结果显示行号）',
+    passageSplitting: '长文档拆分为段落索引（结果显示命中的段落）',
+    tokenizer: '中文分词方式',
+    tokenizerNgram: 'n-gram（默认，召回率高，索引较大）',
+    tokenizerJieba: '词典分词（jieba，结果更精确）',
+    tokenizerHint: '切换分词方式后会自动重建索引',
+    rebuildRequired: '已有索引重建正在进行，以上更改尚未生效，请在其完成后到索引管理页重建索引',
+    userDictionary: '用户词典',
+    userDictionaryPlaceholder: '用户词典文件路径（每行：词语 [词频] [词性]）',
+    pinyin: '拼音搜索（文件名）',
+    pinyinContent: '同时为正文建立拼音索引（索引会明显变大）',
+    pinyinHint: '可用全拼或首字母搜索，如 luyouqi 或 lyq 匹配“路由器”；修改后会自动重建索引',
+    fuzzyContent: '模糊搜索同时匹配正文中的单词（索引会变大）',
+    fuzzyContentHint: '未开启时模糊搜索只容错文件名、标题和代码符号中的单词；修改后会自动重建索引',
+    regexIndex: '为正则搜索建立原文词项索引（索引会变大）',
+    regexIndexHint: '正则搜索先按原文词项筛选候选文档，未开启时不能使用正则搜索；修改后会自动重建索引',
+    textLanguage: '英文等文本的分析语言',
+    textLanguageOff: '不使用（按 n-gram 分词）',
+    textLanguageHint: '设置后英文等按单词分词，去除停用词和重音符号并提取词干（如 configuration 可匹配 configure）；修改后会自动重建索引',
+    textLanguages: {
+      english: '英语',
+      french: '法语',
+      german: '德语',
+      spanish: '西班牙语',
+      italian: '意大利语',
+      portuguese: '葡萄牙语',
+      dutch: '荷兰语',
+      swedish: '瑞典语',
+      russian: '俄语',
+    },
     excludePatterns: '排除文件模式',
     delete: '删除',
     addExclude: '添加排除模式',
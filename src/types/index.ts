@@ -4,16 +4,84 @@ export interface SearchResult {
   content: string;
   filePath: string;
   fileType: string;
+  mimeType?: string | null;
   modifiedTime: number;
   score: number;
   highlights: string[];
+  highlightTerms?: string[];
+  section?: string | null;
+  line?: number | null;
+  lines?: number[];
+  row?: number | null;
+  passage?: PassageInfo | null;
+  image?: ImageMeta | null;
+  media?: MediaMeta | null;
+}
+
+export interface ImageMeta {
+  takenAt?: number | null;
+  camera?: string | null;
+  width?: number | null;
+  height?: number | null;
+  gps?: [number, number] | null;
+}
+
+export interface MediaMeta {
+  title?: string | null;
+  artist?: string | null;
+  album?: string | null;
+  year?: number | null;
+  duration?: number | null; // seconds
+}
+
+export interface PassageInfo {
+  kind: 'slide' | 'sheet' | 'lines' | 'text';
+  index: number;
+  name?: string | null;
+  hits: number;
 }
 
 export interface SearchResponse {
   results: SearchResult[];
   totalCount: number;
+  // 命中文件超过单次搜索的上限，只能分页浏览排名最前的文件
+  truncated?: boolean;
   searchTime: number;
   hasMore: boolean;
+  facets?: SearchFacets | null;
+}
+
+// 文件名索引的搜索结果，覆盖所有类型的文件
+export interface FilenameResult {
+  name: string;
+  filePath: string;
+  fileType: string;
+  fileSize: number;
+  modifiedTime: number;
+}
+
+export interface FilenameSearchResponse {
+  results: FilenameResult[];
+  totalCount: number;
+}
+
+// 搜索失败时后端返回的错误，message 已按当前语言本地化
+export interface SearchError {
+  code: 'emptyValue' | 'invalidDate' | 'invalidSize' | 'booleanOperator' | 'invalidRegex' | 'regexNoIndex' | 'regexTooBroad' | 'internal';
+  token?: string | null;
+  message: string;
+}
+
+export interface FacetCount {
+  value: string;
+  count: number;
+}
+
+// 命中文件按文件类型、索引目录和修改时间分桶（today、week、month、older）的计数
+export interface SearchFacets {
+  fileTypes: FacetCount[];
+  directories: FacetCount[];
+  dates: FacetCount[];
 }
 
 export interface DirectoryConfig {
@@ -37,13 +105,29 @@ export interface SearchFilters {
     start?: number;
     end?: number;
   };
-  datePreset?: 'any' | 'lastDay' | 'lastWeek' | 'lastMonth';
+  datePreset?: 'any' | 'today' | 'thisWeek' | 'thisMonth';
+  takenRange?: {
+    start?: number;
+    end?: number;
+  };
+  camera?: string;
+  includeDirs?: string[];
+  excludeDirs?: string[];
   fileSizeRange?: {
     min?: number;
     max?: number;
   };
 }
 
+export type SearchSort = 'relevance' | 'newest' | 'oldest' | 'largest' | 'smallest' | 'name';
+
+export interface FileTypeGroup {
+  id: string;
+  name?: string | null;
+  extensions: string[];
+  builtin: boolean;
+}
+
 export interface SearchHistory {
   id: string;
   query: string;
@@ -64,13 +148,30 @@ export interface AppSettings {
   search: {
     resultsPerPage: number;
     matchPrecision: number;
+    // 模糊搜索允许的最大编辑距离（拼写错误的字符数）
+    fuzzyDistance?: 1 | 2;
     enableHighlighting: boolean;
+    // 查询中未写运算符的多个词之间的默认运算
+    defaultOperator?: 'and' | 'or';
+    fileTypeGroups?: Omit<FileTypeGroup, 'builtin'>[];
   };
   indexing: {
     autoUpdate: boolean;
     updateInterval: number;
     excludePatterns: string[];
     maxFileSize: number;
+    lineChunking?: boolean;
+    // 长文档按该字符数拆分为段落索引，为 0 或未设置时不拆分
+    passageSize?: number;
+    tokenizer?: 'ngram' | 'jieba';
+    userDictionary?: string;
+    pinyin?: boolean;
+    pinyinContent?: boolean;
+    // 模糊搜索同时匹配内容中的单词，未开启时只匹配文件名和标题
+    fuzzyContent?: boolean;
+    // 为正则搜索建立原文词项索引（默认开启），未开启时不能使用正则搜索
+    regexIndex?: boolean;
+    textLanguage?: string;
   };
   ui: {
     theme: 'light' | 'dark' | 'system';